@@ -0,0 +1,61 @@
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static STEP: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+/// Makes audited operations fail on overflow instead of wrapping.
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Runs `f` with `step` named in the overflow reports.
+pub fn within<T, F>(step: &str, f: F) -> T
+where F: FnOnce() -> T {
+    let previous = STEP.replace(step.to_string());
+    let result = f();
+    STEP.set(previous);
+    result
+}
+
+/// The step of the enclosing `within`, to pass on to helper threads.
+pub fn current() -> String {
+    STEP.with_borrow(Clone::clone)
+}
+
+/// An arithmetic operation named `op`: its `checked` result is required
+/// in the audit mode, the `wrapped` one is used otherwise.
+pub fn op<T>(op: &str, checked: Option<T>, wrapped: T) -> Result<T, String> {
+    check(ENABLED.load(Ordering::Relaxed), op, checked, wrapped)
+}
+
+fn check<T>(
+    enabled: bool, op: &str, checked: Option<T>, wrapped: T
+) -> Result<T, String> {
+    match checked {
+        Some(value) => Ok(value),
+        None if enabled => Err(STEP.with_borrow(
+            |step| format!("Overflow in {}: {}", step, op)
+        )),
+        None => Ok(wrapped),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn audited() {
+        let x = 250_u8;
+        assert_eq!(check(true, "x + 1", x.checked_add(1), x.wrapping_add(1)), Ok(251));
+        assert_eq!(check(false, "x + 10", x.checked_add(10), x.wrapping_add(10)), Ok(4));
+        let overflow = within("Day 00 step 1", || {
+            check(true, "x + 10", x.checked_add(10), x.wrapping_add(10))
+        });
+        assert_eq!(overflow, Err("Overflow in Day 00 step 1: x + 10".to_string()));
+    }
+}
@@ -0,0 +1,108 @@
+use std::fs;
+use std::time::{Duration, Instant};
+
+use crate::cancel::{self, Token};
+use crate::input::read_lines;
+use crate::registry::{Answers, Entry};
+use crate::status::short_duration;
+
+/// Inputs this many times slower than the median one are flagged.
+const SLOW_FACTOR: u32 = 4;
+
+pub struct Row {
+    pub file: String,
+    pub answers: Result<Answers, String>,
+    pub elapsed: Duration,
+}
+
+/// Solves every file of the directory, in name order. A file that can't
+/// be read gets a row with the error.
+pub fn run(entry: &Entry, dir: &str, timeout: Option<Duration>) -> Result<Vec<Row>, String> {
+    let mut files = fs::read_dir(dir).map_err(|e| format!("{}: {}", dir, e))?
+        .map(|e| e.map(|e| e.path()).map_err(|e| e.to_string()))
+        .collect::<Result<Vec<_>, _>>()?;
+    files.retain(|p| p.is_file());
+    files.sort();
+    Ok(files.iter().map(|path| {
+        let file = path.file_name().map_or("?".into(), |n| n.to_string_lossy()).to_string();
+        let token = Token::new(timeout);
+        let started = Instant::now();
+        let answers = read_lines(path)
+            .and_then(|lines| cancel::scope(&token, || (entry.solve)(lines)));
+        Row { file, answers, elapsed: started.elapsed() }
+    }).collect())
+}
+
+/// A table of answers and timings, with a note on erroneous and slow inputs.
+pub fn render(rows: &[Row]) -> Vec<String> {
+    let mut times = rows.iter().map(|r| r.elapsed).collect::<Vec<_>>();
+    times.sort();
+    let median = times.get(times.len() / 2).copied().unwrap_or_default();
+    let mut table = vec![["input", "step 1", "step 2", "time", ""].map(str::to_string)];
+    for row in rows {
+        let answers = match &row.answers {
+            Ok(answers) => answers.clone().map(|a| a.unwrap_or_else(|e| format!("Error: {}", e))),
+            Err(e) => [format!("Error: {}", e), String::new()],
+        };
+        let error = row.answers.as_ref().map_or(true, |a| a.iter().any(Result::is_err));
+        let slow = row.elapsed > median * SLOW_FACTOR && row.elapsed > Duration::from_millis(1);
+        let flag = match (error, slow) {
+            (true, _) => "ERROR",
+            (_, true) => "SLOW",
+            _ => "",
+        };
+        let [a1, a2] = answers;
+        table.push([row.file.clone(), a1, a2, short_duration(row.elapsed), flag.to_string()]);
+    }
+    let widths = (0..5)
+        .map(|i| table.iter().map(|r| r[i].chars().count()).max().unwrap_or(0))
+        .collect::<Vec<_>>();
+    table.iter().map(|row| {
+        row.iter().zip(&widths)
+            .map(|(cell, w)| format!("{:<w$}", cell, w = w))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry;
+
+    #[test]
+    fn run_and_render() {
+        let dir = std::env::temp_dir().join(format!("aoc-batch-{}", std::process::id()));
+        fs::create_dir_all(&dir).expect("Should be writable");
+        fs::write(dir.join("alice.txt"), "L68\nL30\nR48\n").expect("Should be writable");
+        fs::write(dir.join("bob.txt"), "X1\n").expect("Should be writable");
+        fs::write(dir.join("carol.txt"), b"L\xff\n").expect("Should be writable");
+        let entry = registry::find(1).expect("Should be known");
+        let rows = run(entry, dir.to_str().expect("Should be UTF-8"), None)
+            .expect("Should be readable");
+        let lines = render(&rows);
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("input"));
+        assert!(lines[1].starts_with("alice.txt  1"));
+        assert!(lines[2].starts_with("bob.txt    Error:"));
+        assert!(lines[2].ends_with("ERROR"));
+        assert!(lines[3].starts_with("carol.txt  Error:"));
+        assert!(lines[3].ends_with("ERROR"));
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn flags_slow_inputs() {
+        let row = |file: &str, millis| Row {
+            file: file.to_string(),
+            answers: Ok([Ok("1".to_string()), Ok("2".to_string())]),
+            elapsed: Duration::from_millis(millis),
+        };
+        let lines = render(&[row("a", 10), row("b", 12), row("c", 100)]);
+        assert!(!lines[1].ends_with("SLOW"));
+        assert!(!lines[2].ends_with("SLOW"));
+        assert!(lines[3].ends_with("SLOW"));
+    }
+}
@@ -0,0 +1,145 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::input::read_lines;
+use crate::registry::Entry;
+use crate::status::short_duration;
+use crate::types::slug;
+
+/// The best time of every phase ("parse", "step1", "step2", and "step1:name"
+/// for a named variant) of every day.
+pub type Timings = BTreeMap<(String, String), Duration>;
+
+/// Times the phases of the day over `runs` runs, keeping the fastest.
+pub fn measure(
+    entry: &Entry, input_dir: &Path, runs: usize, timeout: Option<Duration>, timings: &mut Timings
+) -> Result<(), String> {
+    let day = slug(&(entry.name)());
+    let lines = read_lines(input_dir.join((entry.input_file)()))?;
+    for _ in 0..runs.max(1) {
+        let profile = (entry.profile)(lines.clone(), timeout)?;
+        // a failing step would pass for a fast one
+        for (i, (answer, _)) in profile.steps.iter().enumerate() {
+            answer.as_ref().map_err(|e| format!("{} step {}: {}", day, i + 1, e))?;
+        }
+        let phases = [
+            ("parse", profile.parse),
+            ("step1", profile.steps[0].1),
+            ("step2", profile.steps[1].1),
+        ];
+        for (phase, elapsed) in phases {
+            let best = timings.entry((day.clone(), phase.to_string())).or_insert(elapsed);
+            *best = elapsed.min(*best);
+        }
+        for step in [1, 2] {
+            for name in (entry.variants)(step) {
+                let (answer, elapsed) = (entry.variant)(lines.clone(), step, name, timeout)?;
+                answer.map_err(|e| format!("{} step {} {}: {}", day, step, name, e))?;
+                let phase = format!("step{}:{}", step, name);
+                let best = timings.entry((day.clone(), phase)).or_insert(elapsed);
+                *best = elapsed.min(*best);
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn save(path: &Path, timings: &Timings) -> Result<(), String> {
+    let text = timings.iter()
+        .map(|((day, phase), t)| format!("{} {} {}\n", day, phase, t.as_secs_f64()))
+        .collect::<String>();
+    path.parent().map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::write(path, text))
+        .map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+pub fn load(path: &Path) -> Result<Timings, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    text.lines().map(|line| {
+        let [day, phase, secs] = line.split_whitespace().collect::<Vec<_>>()[..] else {
+            return Err(format!("Bad baseline line: {}", line))
+        };
+        let secs = secs.parse::<f64>().ok()
+            .and_then(|s| Duration::try_from_secs_f64(s).ok())
+            .map(Result::Ok)
+            .unwrap_or(Err(format!("Bad baseline line: {}", line)))?;
+        Ok(((day.to_string(), phase.to_string()), secs))
+    }).collect()
+}
+
+/// A line per phase measured both times, and how many of them got slower
+/// by more than `threshold` percent.
+pub fn compare(baseline: &Timings, current: &Timings, threshold: f64) -> (Vec<String>, usize) {
+    let mut lines = Vec::new();
+    let mut slower = 0;
+    for ((day, phase), now) in current {
+        let Some(before) = baseline.get(&(day.clone(), phase.clone())) else { continue };
+        let change = 100.0 * (now.as_secs_f64() / before.as_secs_f64().max(1e-9) - 1.0);
+        let flag = if change > threshold { slower += 1; "  SLOWER" } else { "" };
+        lines.push(format!(
+            "{} {} {:>5} -> {:>5} {:>+5.0}%{}",
+            day, phase, short_duration(*before), short_duration(*now), change, flag
+        ));
+    }
+    (lines, slower)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::{self, Profile};
+
+    fn timings(entries: &[(&str, &str, u64)]) -> Timings {
+        entries.iter()
+            .map(|(d, p, ms)| ((d.to_string(), p.to_string()), Duration::from_millis(*ms)))
+            .collect()
+    }
+
+    #[test]
+    fn save_and_load() {
+        let path = std::env::temp_dir()
+            .join(format!("aoc-bench-{}", std::process::id()))
+            .join("base");
+        let t = timings(&[("day01", "parse", 1), ("day01", "step1", 20)]);
+        save(&path, &t).expect("Should be writable");
+        assert_eq!(load(&path), Ok(t));
+        fs::write(&path, "day01 parse -1\n").expect("Should be writable");
+        assert_eq!(load(&path), Err("Bad baseline line: day01 parse -1".to_string()));
+        let _ = fs::remove_dir_all(path.parent().expect("Should have a parent"));
+    }
+
+    #[test]
+    fn failing_step() {
+        let dir = std::env::temp_dir().join(format!("aoc-bench-failing-{}", std::process::id()));
+        fs::create_dir_all(&dir).expect("Should be writable");
+        fs::write(dir.join("day01.txt"), "L68\n").expect("Should be writable");
+        let entry = Entry {
+            profile: |_, _| Ok(Profile {
+                parse: Duration::ZERO,
+                steps: [
+                    (Ok("1".to_string()), Duration::ZERO),
+                    (Err("Bad".to_string()), Duration::ZERO),
+                ],
+                svg: None,
+            }),
+            ..*registry::find(1).expect("Should exist")
+        };
+        let mut t = Timings::new();
+        assert_eq!(measure(&entry, &dir, 1, None, &mut t), Err("day01 step 2: Bad".to_string()));
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn compare() {
+        let before = timings(&[("day04", "step1", 100), ("day08", "step1", 100)]);
+        let after = timings(&[("day04", "step1", 105), ("day08", "step1", 150), ("day09", "step1", 1)]);
+        let (lines, slower) = super::compare(&before, &after, 10.0);
+        assert_eq!(lines, vec![
+            "day04 step1 100ms -> 105ms    +5%",
+            "day08 step1 100ms -> 150ms   +50%  SLOWER",
+        ]);
+        assert_eq!(slower, 1);
+    }
+}
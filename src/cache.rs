@@ -0,0 +1,185 @@
+use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use crate::types::slug;
+
+/// What an answer depends on.
+#[derive(Debug, PartialEq)]
+pub struct Key {
+    pub day: String,
+    pub step: usize,
+    pub input: u64,
+    pub build: u64,
+}
+
+impl Key {
+    pub fn new(day: &str, step: usize, lines: &[String]) -> Key {
+        Key {
+            day: slug(day),
+            step,
+            input: hash(lines),
+            build: build_fingerprint(),
+        }
+    }
+
+    fn file_name(&self) -> String {
+        format!("{}-step{}-{:016x}-{:016x}", self.day, self.step, self.input, self.build)
+    }
+
+    fn from_file_name(name: &str) -> Option<Key> {
+        let [day, step, input, build] = name.split('-').collect::<Vec<_>>()[..]
+        else { return None };
+        Some(Key {
+            day: day.to_string(),
+            step: step.strip_prefix("step")?.parse().ok()?,
+            input: u64::from_str_radix(input, 16).ok()?,
+            build: u64::from_str_radix(build, 16).ok()?,
+        })
+    }
+}
+
+/// The latest computed (not cached) result of a step.
+#[derive(Debug, PartialEq)]
+pub struct LastRun {
+    pub answer: Result<String, String>,
+    pub elapsed: Duration,
+}
+
+impl LastRun {
+    fn file_name(day: &str, step: usize) -> String {
+        format!("last-{}-step{}", day, step)
+    }
+
+    fn to_text(&self) -> String {
+        let (tag, text) = match &self.answer {
+            Ok(a) => ("ok", a),
+            Err(e) => ("error", e),
+        };
+        format!("{}\n{} {}", self.elapsed.as_secs_f64(), tag, text)
+    }
+
+    fn from_text(text: &str) -> Option<LastRun> {
+        let (elapsed, result) = text.split_once('\n')?;
+        let elapsed = Duration::try_from_secs_f64(elapsed.parse().ok()?).ok()?;
+        let answer = match result.split_once(' ')? {
+            ("ok", a) => Ok(a.to_string()),
+            ("error", e) => Err(e.to_string()),
+            _ => return None,
+        };
+        Some(LastRun { answer, elapsed })
+    }
+}
+
+/// Answers stored on disk, one file per key.
+pub struct Cache {
+    dir: PathBuf,
+}
+
+impl Cache {
+    pub fn new(dir: impl AsRef<Path>) -> Cache { Cache { dir: dir.as_ref().to_path_buf() } }
+
+    pub fn get(&self, key: &Key) -> Option<String> {
+        fs::read_to_string(self.dir.join(key.file_name())).ok()
+    }
+
+    pub fn put(&self, key: &Key, answer: &str) -> Result<(), String> {
+        fs::create_dir_all(&self.dir)
+            .and_then(|_| fs::write(self.dir.join(key.file_name()), answer))
+            .map_err(|e| format!("{}: {}", self.dir.display(), e))
+    }
+
+    pub fn last_run(&self, day: &str, step: usize) -> Option<LastRun> {
+        fs::read_to_string(self.dir.join(LastRun::file_name(day, step))).ok()
+            .and_then(|text| LastRun::from_text(&text))
+    }
+
+    pub fn put_last_run(&self, day: &str, step: usize, run: &LastRun) -> Result<(), String> {
+        fs::create_dir_all(&self.dir)
+            .and_then(|_| fs::write(self.dir.join(LastRun::file_name(day, step)), run.to_text()))
+            .map_err(|e| format!("{}: {}", self.dir.display(), e))
+    }
+
+    pub fn entries(&self) -> Result<Vec<(Key, String)>, String> {
+        let Ok(dir) = fs::read_dir(&self.dir) else { return Ok(Vec::new()) };
+        let mut entries = Vec::new();
+        for entry in dir {
+            let path = entry.map_err(|e| e.to_string())?.path();
+            let key = path.file_name()
+                .and_then(|n| n.to_str())
+                .and_then(Key::from_file_name);
+            if let Some(key) = key {
+                let answer = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+                entries.push((key, answer));
+            }
+        }
+        entries.sort_by(|(a, _), (b, _)| (&a.day, a.step).cmp(&(&b.day, b.step)));
+        Ok(entries)
+    }
+
+    pub fn clear(&self) -> Result<usize, String> {
+        let entries = self.entries()?;
+        for (key, _) in &entries {
+            fs::remove_file(self.dir.join(key.file_name()))
+                .map_err(|e| e.to_string())?;
+        }
+        Ok(entries.len())
+    }
+}
+
+pub fn hash<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// A hash of the running executable, so any rebuild invalidates answers.
+pub fn build_fingerprint() -> u64 {
+    static FINGERPRINT: OnceLock<u64> = OnceLock::new();
+    *FINGERPRINT.get_or_init(|| {
+        std::env::current_exe()
+            .and_then(fs::read)
+            .map(|bytes| hash(&bytes))
+            .unwrap_or(0)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_file_name() {
+        let key = Key::new("Day 01", 2, &["L1".to_string()]);
+        assert_eq!(key.day, "day01");
+        assert_eq!(Key::from_file_name(&key.file_name()), Some(key));
+        assert_eq!(Key::from_file_name("junk"), None);
+    }
+
+    #[test]
+    fn corrupt_last_run() {
+        assert_eq!(LastRun::from_text("-1\nok 3"), None);
+        assert_eq!(LastRun::from_text("NaN\nok 3"), None);
+        assert!(LastRun::from_text("0.5\nok 3").is_some());
+    }
+
+    #[test]
+    fn put_get_clear() {
+        let dir = std::env::temp_dir().join(format!("aoc-cache-{}", std::process::id()));
+        let cache = Cache::new(dir.to_str().expect("Should be UTF-8"));
+        let key = Key::new("Day 02", 1, &[]);
+        assert_eq!(cache.get(&key), None);
+        cache.put(&key, "42").expect("Should be writable");
+        assert_eq!(cache.get(&key), Some("42".to_string()));
+        assert_eq!(cache.entries().map(|e| e.len()), Ok(1));
+        let run = LastRun { answer: Err("Oops".to_string()), elapsed: Duration::from_millis(5) };
+        cache.put_last_run("day02", 1, &run).expect("Should be writable");
+        assert_eq!(cache.last_run("day02", 1), Some(run));
+        assert_eq!(cache.last_run("day02", 2), None);
+        assert_eq!(cache.clear(), Ok(1));
+        assert_eq!(cache.get(&key), None);
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,88 @@
+use std::cell::RefCell;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// A cooperative cancellation token with an optional deadline.
+#[derive(Clone)]
+pub struct Token {
+    cancelled: Arc<AtomicBool>,
+    deadline: Option<Instant>,
+}
+
+impl Token {
+    pub fn new(timeout: Option<Duration>) -> Token {
+        Token {
+            cancelled: Arc::new(AtomicBool::new(false)),
+            deadline: timeout.map(|t| Instant::now() + t),
+        }
+    }
+
+    #[cfg(test)]
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+            || self.deadline.is_some_and(|d| Instant::now() >= d)
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<Token>> = const { RefCell::new(None) };
+}
+
+/// Runs `f` with `token` visible to the solvers through `check`.
+pub fn scope<T, F>(token: &Token, f: F) -> T
+where F: FnOnce() -> T {
+    let previous = CURRENT.replace(Some(token.clone()));
+    let result = f();
+    CURRENT.set(previous);
+    result
+}
+
+/// The token of the enclosing `scope`, to pass on to helper threads.
+pub fn current() -> Option<Token> {
+    CURRENT.with_borrow(Clone::clone)
+}
+
+/// Fails with a description of the `progress` made so far
+/// when the current token is cancelled or its deadline has passed.
+pub fn check<F>(progress: F) -> Result<(), String>
+where F: FnOnce() -> String {
+    let cancelled = CURRENT.with_borrow(
+        |t| t.as_ref().is_some_and(Token::is_cancelled));
+    if cancelled {
+        Err(format!("Cancelled: {}", progress()))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_outside_of_scope() {
+        assert_eq!(check(|| "nothing".to_string()), Ok(()));
+    }
+
+    #[test]
+    fn check_cancelled() {
+        let token = Token::new(None);
+        assert_eq!(scope(&token, || check(|| "1 step".to_string())), Ok(()));
+        token.cancel();
+        assert_eq!(
+            scope(&token, || check(|| "1 step".to_string())),
+            Err("Cancelled: 1 step".to_string())
+        );
+    }
+
+    #[test]
+    fn check_deadline() {
+        let token = Token::new(Some(Duration::ZERO));
+        assert!(scope(&token, || check(String::new)).is_err());
+    }
+}
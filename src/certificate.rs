@@ -0,0 +1,317 @@
+use std::collections::HashSet;
+
+/// A witness of an answer, cheap to verify against the raw input.
+#[derive(Debug, PartialEq)]
+pub enum Certificate {
+    /// Day03: positions of the chosen digits in every bank.
+    Digits(Vec<Vec<usize>>),
+    /// Day08: pairs of boxes (by line number) in the order they were joined.
+    Joins(Vec<(usize, usize)>),
+    /// Day09: the opposite corners of the rectangle.
+    Corners((usize, usize), (usize, usize)),
+    /// Day10: how many times every button of every machine is pressed.
+    Presses(Vec<Vec<usize>>),
+}
+
+/// How much a valid certificate says about its answer.
+#[derive(Debug, PartialEq)]
+pub enum Proof {
+    /// The answer is the right one.
+    Certified,
+    /// The answer can be reached, but there may be a better one.
+    FeasibleOnly,
+}
+
+/// Verifies the certificate of the answer to a step of a day. The input is
+/// parsed here from scratch, so the solvers' parsers are not trusted either.
+pub fn check(
+    day: usize, step: usize, lines: &[String], answer: &str, certificate: &Certificate
+) -> Result<Proof, String> {
+    let answer = answer.parse::<u128>().map_err(|_| format!("Not a number: {}", answer))?;
+    let (value, proof) = match (day, certificate) {
+        (3, Certificate::Digits(banks)) => (check_digits(lines, step, banks)?, Proof::Certified),
+        (8, Certificate::Joins(joins)) => (check_joins(lines, step, joins)?, Proof::Certified),
+        (9, Certificate::Corners(a, b)) => check_corners(lines, step, a, b)?,
+        (10, Certificate::Presses(presses)) => check_presses(lines, step, presses)?,
+        _ => return Err(format!("Day {} has no certificates of this kind", day)),
+    };
+    if value != answer {
+        return Err(format!("Certificate proves {}, not {}", value, answer))
+    }
+    Ok(proof)
+}
+
+fn numbers<T: std::str::FromStr>(text: &str, separator: char) -> Result<Vec<T>, String> {
+    text.split(separator)
+        .map(|n| n.trim().parse::<T>().map_err(|_| format!("Not a number: {}", n)))
+        .collect()
+}
+
+/// Every pick is the first of the biggest digits it could be, given the
+/// picks before it and the number of picks left after it.
+fn check_digits(lines: &[String], step: usize, banks: &[Vec<usize>]) -> Result<u128, String> {
+    let picks = if step == 1 { 2 } else { 12 };
+    if banks.len() != lines.len() {
+        return Err(format!("{} banks certified, {} given", banks.len(), lines.len()))
+    }
+    let mut sum = 0_u128;
+    for (line, indices) in lines.iter().zip(banks) {
+        let digits = line.bytes()
+            .map(|b| if b.is_ascii_digit() { Ok(b - b'0') } else {
+                Err(format!("Not a digit: {}", b as char))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if indices.len() != picks {
+            return Err(format!("{} digits picked instead of {} in {}", indices.len(), picks, line))
+        }
+        let mut value = 0_u128;
+        let mut from = 0;
+        for (k, &i) in indices.iter().enumerate() {
+            let to = digits.len().checked_sub(picks - k)
+                .map(Result::Ok)
+                .unwrap_or(Err(format!("Bank is too short: {}", line)))?;
+            if i < from || i > to {
+                return Err(format!("Digit {} is out of place in {}", i, line))
+            }
+            if digits[from..=to].iter().any(|d| *d > digits[i]) {
+                return Err(format!("Digit {} could be bigger in {}", i, line))
+            }
+            // a later copy of the biggest digit leaves fewer digits to pick from
+            if digits[from..i].iter().any(|d| *d >= digits[i]) {
+                return Err(format!("Digit {} could be picked earlier in {}", i, line))
+            }
+            value = 10 * value + digits[i] as u128;
+            from = i + 1;
+        }
+        sum += value;
+    }
+    Ok(sum)
+}
+
+/// Joins go from the closest pair up, nothing skipped is closer than the
+/// last join, and they end as soon as a single circuit is left.
+fn check_joins(lines: &[String], step: usize, joins: &[(usize, usize)]) -> Result<u128, String> {
+    let boxes = lines.iter()
+        .map(|l| numbers::<i128>(l, ','))
+        .collect::<Result<Vec<_>, _>>()?;
+    let distance = |(a, b): (usize, usize)| -> Result<i128, String> {
+        let (p, q) = boxes.get(a).zip(boxes.get(b))
+            .map(Result::Ok)
+            .unwrap_or(Err(format!("No such pair of boxes: {}, {}", a, b)))?;
+        Ok(p.iter().zip(q).map(|(x, y)| (x - y) * (x - y)).sum())
+    };
+    let mut seen = HashSet::new();
+    let mut last = 0;
+    for &(a, b) in joins {
+        let d = distance((a, b))?;
+        if a >= b || !seen.insert((a, b)) || d < last {
+            return Err(format!("Join {}, {} is out of order", a, b))
+        }
+        last = d;
+    }
+    for a in 0..boxes.len() {
+        for b in a + 1..boxes.len() {
+            if !seen.contains(&(a, b)) && distance((a, b))? < last {
+                return Err(format!("Boxes {} and {} should be joined", a, b))
+            }
+        }
+    }
+    let mut circuit = (0..boxes.len()).collect::<Vec<_>>();
+    fn root(circuit: &mut [usize], i: usize) -> usize {
+        let mut r = i;
+        while circuit[r] != r { r = circuit[r] }
+        circuit[i] = r;
+        r
+    }
+    let mut circuits = boxes.len();
+    for (n, &(a, b)) in joins.iter().enumerate() {
+        let (ra, rb) = (root(&mut circuit, a), root(&mut circuit, b));
+        if ra == rb { continue }
+        circuit[rb] = ra;
+        circuits -= 1;
+        // the solver stops here even in step 1
+        if circuits == 1 {
+            if n + 1 != joins.len() {
+                return Err("Joins go on after a single circuit is left".to_string())
+            }
+            return Ok((boxes[a][0] * boxes[b][0]) as u128)
+        }
+    }
+    if step == 2 { return Err(format!("{} circuits left", circuits)) }
+    let pairs = boxes.len() * boxes.len().saturating_sub(1) / 2;
+    if joins.len() != pairs.min(1000) {
+        return Err(format!("{} joins instead of {}", joins.len(), pairs.min(1000)))
+    }
+    let mut sizes = vec![0_u128; boxes.len()];
+    for i in 0..boxes.len() { sizes[root(&mut circuit, i)] += 1 }
+    sizes.sort_by(|a, b| b.cmp(a));
+    Ok(sizes.iter().take(3).product())
+}
+
+/// Both corners are red tiles. The area is only certified as the largest
+/// when it covers all the red tiles, as no two of them span more.
+fn check_corners(
+    lines: &[String], step: usize, a: &(usize, usize), b: &(usize, usize)
+) -> Result<(u128, Proof), String> {
+    if step != 1 { return Err("Corners only certify step 1".to_string()) }
+    let tiles = lines.iter()
+        .map(|l| match numbers::<usize>(l, ',')?[..] {
+            [x, y] => Ok((x, y)),
+            _ => Err(format!("Bad tile: {}", l)),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    for (x, y) in [a, b] {
+        if !tiles.contains(&(*x, *y)) {
+            return Err(format!("{},{} is not a red tile", x, y))
+        }
+    }
+    let area = |a: &(usize, usize), b: &(usize, usize)|
+        (a.0.abs_diff(b.0) + 1) as u128 * (a.1.abs_diff(b.1) + 1) as u128;
+    let (xs, ys) = (tiles.iter().map(|t| t.0), tiles.iter().map(|t| t.1));
+    let low = (xs.clone().min().unwrap_or(0), ys.clone().min().unwrap_or(0));
+    let high = (xs.max().unwrap_or(0), ys.max().unwrap_or(0));
+    let bounds = area(&low, &high);
+    let proof = if area(a, b) == bounds { Proof::Certified } else { Proof::FeasibleOnly };
+    Ok((area(a, b), proof))
+}
+
+/// The presses light up the target in step 1 and add up to the joltages
+/// in step 2, the answer is their count. It is only certified as the
+/// fewest when every machine meets `fewest_bound`.
+fn check_presses(
+    lines: &[String], step: usize, presses: &[Vec<usize>]
+) -> Result<(u128, Proof), String> {
+    let mut proof = Proof::Certified;
+    if presses.len() != lines.len() {
+        return Err(format!("{} machines certified, {} given", presses.len(), lines.len()))
+    }
+    let mut total = 0_u128;
+    for (line, counts) in lines.iter().zip(presses) {
+        let words = line.split_whitespace().collect::<Vec<_>>();
+        let [lights, buttons @ .., joltages] = &words[..] else {
+            return Err(format!("Bad machine: {}", line))
+        };
+        let lights = inside(lights, '[', ']')?;
+        let target = if step == 1 {
+            lights.chars().map(|c| (c == '#') as usize).collect::<Vec<_>>()
+        } else {
+            numbers::<usize>(inside(joltages, '{', '}')?, ',')?
+        };
+        if counts.len() != buttons.len() {
+            return Err(format!("{} buttons pressed instead of {}", counts.len(), buttons.len()))
+        }
+        let buttons = buttons.iter()
+            .map(|b| numbers::<usize>(inside(b, '(', ')')?, ','))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut state = vec![0_usize; target.len()];
+        for (button, count) in buttons.iter().zip(counts) {
+            for &i in button {
+                let counter = state.get_mut(i)
+                    .map(Result::Ok)
+                    .unwrap_or(Err(format!("Button wired to nothing: {}", line)))?;
+                *counter += count;
+            }
+        }
+        if step == 1 { state.iter_mut().for_each(|s| *s %= 2) }
+        if state != target {
+            return Err(format!("Presses reach {:?} instead of {:?}", state, target))
+        }
+        let count = counts.iter().sum::<usize>();
+        if count > fewest_bound(&buttons, &target) { proof = Proof::FeasibleOnly }
+        total += count as u128;
+    }
+    Ok((total, proof))
+}
+
+/// At least how many presses reach the target: a press adds at most one
+/// to a counter, or toggles a light, and touches at most the biggest
+/// button's worth of them.
+fn fewest_bound(buttons: &[Vec<usize>], target: &[usize]) -> usize {
+    let widest = buttons.iter().map(Vec::len).max().unwrap_or(0).max(1);
+    let highest = target.iter().copied().max().unwrap_or(0);
+    highest.max(target.iter().sum::<usize>().div_ceil(widest))
+}
+
+fn inside(word: &str, left: char, right: char) -> Result<&str, String> {
+    word.strip_prefix(left)
+        .and_then(|w| w.strip_suffix(right))
+        .map(Result::Ok)
+        .unwrap_or(Err(format!("Bad machine part: {}", word)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &[&str]) -> Vec<String> {
+        text.iter().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn digits() {
+        let banks = lines(&["987654321111111", "811111111111119"]);
+        let good = Certificate::Digits(vec![vec![0, 1], vec![0, 14]]);
+        assert_eq!(check(3, 1, &banks, "187", &good), Ok(Proof::Certified));
+        let worse = Certificate::Digits(vec![vec![1, 2], vec![0, 14]]);
+        assert_eq!(
+            check(3, 1, &banks, "176", &worse),
+            Err("Digit 1 could be bigger in 987654321111111".to_string())
+        );
+        let later = Certificate::Digits(vec![vec![1, 2]]);
+        assert_eq!(
+            check(3, 1, &lines(&["991"]), "91", &later),
+            Err("Digit 1 could be picked earlier in 991".to_string())
+        );
+        assert!(check(3, 1, &lines(&["9x1"]), "91", &good).is_err());
+    }
+
+    #[test]
+    fn presses() {
+        let machines = lines(&["[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}"]);
+        let init = Certificate::Presses(vec![vec![0, 0, 0, 0, 1, 1]]);
+        // one press of a two-light button could do as far as the bound knows
+        assert_eq!(check(10, 1, &machines, "2", &init), Ok(Proof::FeasibleOnly));
+        let single = lines(&["[.##.] (1,2) (0,3) {0,1,1,0}"]);
+        let one = Certificate::Presses(vec![vec![1, 0]]);
+        assert_eq!(check(10, 1, &single, "1", &one), Ok(Proof::Certified));
+        assert_eq!(
+            check(10, 1, &machines, "1", &init),
+            Err("Certificate proves 2, not 1".to_string())
+        );
+        let power = Certificate::Presses(vec![vec![1, 3, 0, 3, 1, 2]]);
+        // 19 joltages at most 2 at a time take 10 presses
+        assert_eq!(check(10, 2, &machines, "10", &power), Ok(Proof::Certified));
+        assert!(check(10, 2, &machines, "2", &init).is_err());
+        let longer = Certificate::Presses(vec![vec![2, 0, 0, 0, 1, 1]]);
+        assert_eq!(check(10, 1, &machines, "4", &longer), Ok(Proof::FeasibleOnly));
+    }
+
+    #[test]
+    fn joins() {
+        let boxes = lines(&["0,0,0", "1,0,0", "10,0,0"]);
+        let all = Certificate::Joins(vec![(0, 1), (1, 2)]);
+        assert_eq!(check(8, 2, &boxes, "10", &all), Ok(Proof::Certified));
+        let skipping = Certificate::Joins(vec![(1, 2), (0, 2)]);
+        assert_eq!(
+            check(8, 2, &boxes, "0", &skipping),
+            Err("Boxes 0 and 1 should be joined".to_string())
+        );
+        let boxes = lines(&["0,0,0", "1,0,0", "10,0,0", "11,0,0"]);
+        let early = Certificate::Joins(vec![(0, 1), (2, 3), (1, 2)]);
+        assert_eq!(check(8, 1, &boxes, "10", &early), Ok(Proof::Certified));
+    }
+
+    #[test]
+    fn corners() {
+        let tiles = lines(&["1,1", "4,1", "4,3", "1,3"]);
+        let best = Certificate::Corners((1, 1), (4, 3));
+        assert_eq!(check(9, 1, &tiles, "12", &best), Ok(Proof::Certified));
+        let smaller = Certificate::Corners((1, 1), (4, 1));
+        assert_eq!(check(9, 1, &tiles, "4", &smaller), Ok(Proof::FeasibleOnly));
+        let inside = Certificate::Corners((1, 1), (5, 1));
+        assert_eq!(
+            check(9, 1, &tiles, "5", &inside),
+            Err("5,1 is not a red tile".to_string())
+        );
+    }
+}
@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Command line arguments split into positional ones and `--name value`
+/// options. Names listed as switches take no value.
+pub struct Args {
+    pub positional: Vec<String>,
+    options: HashMap<String, Option<String>>,
+}
+
+impl Args {
+    pub fn parse<I>(args: I, switches: &[&str]) -> Result<Args, String>
+    where I: IntoIterator<Item = String> {
+        let mut positional = Vec::new();
+        let mut options = HashMap::new();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if !arg.starts_with("--") {
+                positional.push(arg);
+            } else if switches.contains(&arg.as_str()) {
+                options.insert(arg, None);
+            } else {
+                let value = args.next()
+                    .map(Result::Ok)
+                    .unwrap_or(Err(format!("Value expected: {}", arg)))?;
+                options.insert(arg, Some(value));
+            }
+        }
+        Ok(Args { positional, options })
+    }
+
+    pub fn has(&self, name: &str) -> bool {
+        self.options.contains_key(name)
+    }
+
+    pub fn get<T>(&self, name: &str) -> Result<Option<T>, String>
+    where T: FromStr, T::Err: ToString {
+        match self.options.get(name) {
+            Some(Some(value)) => value.parse::<T>()
+                .map(Some)
+                .map_err(|err| format!("{}: {}", name, err.to_string())),
+            Some(None) => Err(format!("Value expected: {}", name)),
+            None => Ok(None),
+        }
+    }
+
+    /// A duration given in (fractional) seconds.
+    pub fn seconds(&self, name: &str) -> Result<Option<Duration>, String> {
+        self.get::<f64>(name)?
+            .map(|secs| Duration::try_from_secs_f64(secs)
+                .map_err(|err| format!("{}: {}", name, err)))
+            .transpose()
+    }
+
+    pub fn days(&self) -> Result<Vec<usize>, String> {
+        self.positional.iter()
+            .map(|s| s.parse::<usize>()
+                 .map_err(|err| format!("{}: {}", s, err)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Args {
+        Args::parse(line.split_whitespace().map(ToOwned::to_owned), &["--fast"])
+            .expect("Should be parsable")
+    }
+
+    #[test]
+    fn parse() {
+        let a = args("1 --seed 42 --fast 3");
+        assert_eq!(a.days(), Ok(vec![1, 3]));
+        assert!(a.has("--fast"));
+        assert_eq!(a.get::<u64>("--seed"), Ok(Some(42)));
+        assert_eq!(a.get::<u64>("--size"), Ok(None));
+        assert!(Args::parse(vec!["--seed".to_string()], &[]).is_err());
+        let timeout = args("--timeout 1.5").seconds("--timeout");
+        assert_eq!(timeout, Ok(Some(Duration::from_millis(1500))));
+        assert!(args("--timeout -1").seconds("--timeout").is_err());
+        assert!(args("--timeout NaN").seconds("--timeout").is_err());
+    }
+}
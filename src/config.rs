@@ -0,0 +1,200 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::cli::Args;
+use crate::registry;
+
+/// Defaults for every command, from an optional `aoc.toml` at the repo
+/// root. Command line flags override them. The timeout bounds every step
+/// a command solves, or every query of the REPL.
+#[derive(Debug, PartialEq)]
+pub struct Config {
+    pub input_dir: PathBuf,
+    /// The days to run when none are named.
+    pub days: Vec<usize>,
+    /// The report format, "md" or "html".
+    pub format: String,
+    pub timeout: Option<Duration>,
+    /// Worker threads of parallel solvers, 0 for one per core.
+    pub threads: Option<usize>,
+    pub cache_dir: PathBuf,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            input_dir: PathBuf::from("input"),
+            days: registry::DAYS.iter().map(|e| e.day).collect(),
+            format: "md".to_string(),
+            timeout: None,
+            threads: None,
+            cache_dir: PathBuf::from(".cache"),
+        }
+    }
+}
+
+impl Config {
+    /// The defaults when there is no such file.
+    pub fn load(path: &Path) -> Result<Config, String> {
+        match fs::read_to_string(path) {
+            Ok(text) => Config::parse(&text).map_err(|e| format!("{}: {}", path.display(), e)),
+            Err(_) if !path.exists() => Ok(Config::default()),
+            Err(e) => Err(format!("{}: {}", path.display(), e)),
+        }
+    }
+
+    /// The subset of TOML we need: `key = value` lines with strings,
+    /// numbers and arrays of numbers, and `#` comments.
+    pub fn parse(text: &str) -> Result<Config, String> {
+        let mut config = Config::default();
+        for (n, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') { continue }
+            let at_line = |e: String| format!("line {}: {}", n + 1, e);
+            let (key, value) = line.split_once('=')
+                .map(Result::Ok)
+                .unwrap_or(Err(format!("key = value expected: {}", line)))
+                .map_err(at_line)?;
+            let value = Value::parse(value).map_err(at_line)?;
+            match key.trim() {
+                "input_dir" => config.input_dir = value.string().map_err(at_line)?.into(),
+                "cache_dir" => config.cache_dir = value.string().map_err(at_line)?.into(),
+                "format" => config.format = value.string().map_err(at_line)?,
+                "days" => config.days = value.numbers().map_err(at_line)?,
+                "threads" => config.threads = Some(value.number().map_err(at_line)?),
+                "timeout" => config.timeout = Some(value.seconds().map_err(at_line)?),
+                other => return Err(at_line(format!("Unknown key: {}", other))),
+            }
+        }
+        Ok(config)
+    }
+
+    /// Applies the `--input-dir`, `--cache-dir`, `--format`, `--timeout`
+    /// and `--threads` flags.
+    pub fn overridden(mut self, args: &Args) -> Result<Config, String> {
+        if let Some(dir) = args.get::<String>("--input-dir")? { self.input_dir = dir.into() }
+        if let Some(dir) = args.get::<String>("--cache-dir")? { self.cache_dir = dir.into() }
+        if let Some(format) = args.get("--format")? { self.format = format }
+        if let Some(timeout) = args.seconds("--timeout")? { self.timeout = Some(timeout) }
+        if let Some(threads) = args.get("--threads")? { self.threads = Some(threads) }
+        Ok(self)
+    }
+
+    /// Where a day's input file lives.
+    pub fn input(&self, file: &str) -> PathBuf {
+        self.input_dir.join(file)
+    }
+
+    /// The named days, or the default ones.
+    pub fn days(&self, args: &Args) -> Result<Vec<usize>, String> {
+        let days = args.days()?;
+        Ok(if days.is_empty() { self.days.clone() } else { days })
+    }
+}
+
+enum Value {
+    String(String),
+    Bare(String),
+}
+
+impl Value {
+    fn parse(raw: &str) -> Result<Value, String> {
+        let raw = raw.trim();
+        match raw.strip_prefix('"') {
+            Some(rest) => {
+                let (s, after) = rest.split_once('"')
+                    .map(Result::Ok)
+                    .unwrap_or(Err(format!("Unterminated string: {}", raw)))?;
+                let after = after.trim();
+                if !after.is_empty() && !after.starts_with('#') {
+                    return Err(format!("Unexpected after string: {}", after))
+                }
+                Ok(Value::String(s.to_string()))
+            },
+            None => Ok(Value::Bare(raw.split('#').next().unwrap_or("").trim().to_string())),
+        }
+    }
+
+    fn string(self) -> Result<String, String> {
+        match self {
+            Value::String(s) => Ok(s),
+            Value::Bare(s) => Err(format!("String expected: {}", s)),
+        }
+    }
+
+    fn bare(&self) -> Result<&str, String> {
+        match self {
+            Value::Bare(s) => Ok(s),
+            Value::String(s) => Err(format!("Number expected: \"{}\"", s)),
+        }
+    }
+
+    fn number(&self) -> Result<usize, String> {
+        let s = self.bare()?;
+        s.parse::<usize>().map_err(|e| format!("{}: {}", s, e))
+    }
+
+    fn seconds(&self) -> Result<Duration, String> {
+        let s = self.bare()?;
+        s.parse::<f64>()
+            .map_err(|e| e.to_string())
+            .and_then(|secs| Duration::try_from_secs_f64(secs).map_err(|e| e.to_string()))
+            .map_err(|e| format!("{}: {}", s, e))
+    }
+
+    fn numbers(&self) -> Result<Vec<usize>, String> {
+        let s = self.bare()?;
+        let items = s.strip_prefix('[')
+            .and_then(|s| s.strip_suffix(']'))
+            .map(Result::Ok)
+            .unwrap_or(Err(format!("Array expected: {}", s)))?;
+        items.split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(|item| item.parse::<usize>().map_err(|e| format!("{}: {}", item, e)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let config = Config::parse(
+            "# puzzles\ninput_dir = \"puzzles\" # mine\ndays = [1, 3, 5,]\n\
+             format = \"html\"\ntimeout = 2.5\nthreads = 4\ncache_dir = \"/tmp/aoc\"\n"
+        ).expect("Should be parsable");
+        assert_eq!(config, Config {
+            input_dir: PathBuf::from("puzzles"),
+            days: vec![1, 3, 5],
+            format: "html".to_string(),
+            timeout: Some(Duration::from_millis(2500)),
+            threads: Some(4),
+            cache_dir: PathBuf::from("/tmp/aoc"),
+        });
+        assert_eq!(Config::parse(""), Ok(Config::default()));
+        assert!(Config::parse("days = 1").is_err());
+        assert!(Config::parse("threads = \"4\"").is_err());
+        assert!(Config::parse("color = \"red\"").is_err());
+    }
+
+    #[test]
+    fn overridden() {
+        let args = Args::parse(
+            "--input-dir other --timeout 1 7".split_whitespace().map(ToOwned::to_owned), &[]
+        ).expect("Should be parsable");
+        let config = Config::parse("input_dir = \"puzzles\"\ndays = [2]\nthreads = 3")
+            .and_then(|c| c.overridden(&args))
+            .expect("Should be valid");
+        assert_eq!(config.input("day01.txt"), PathBuf::from("other/day01.txt"));
+        assert_eq!(config.timeout, Some(Duration::from_secs(1)));
+        assert_eq!(config.threads, Some(3));
+        assert_eq!(config.days(&args), Ok(vec![7]));
+        let negative = Args::parse(["--timeout", "-1"].map(ToOwned::to_owned), &[])
+            .expect("Should be parsable");
+        assert!(Config::default().overridden(&negative).is_err());
+    }
+}
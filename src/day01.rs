@@ -1,5 +1,6 @@
-use crate::types::Day;
-use crate::input::read_lines;
+use crate::audit;
+use crate::types::{Day, unknown_variant};
+use crate::rng::Rng;
 
 type Step = (Dir, usize);
 
@@ -34,20 +35,23 @@ struct State {
     position: i64,
 }
 
-fn stepper1(state: State, dir_and_count: &(Dir, usize)) -> State {
-    let (dir, count) = dir_and_count;
+fn stepper1(state: State, dir_and_count: &(Dir, usize)) -> Result<State, String> {
+    let (dir, raw_count) = dir_and_count;
+    let count = audit::op(
+        "count as i64", i64::try_from(*raw_count).ok(), *raw_count as i64)?;
+    let p = state.position;
     let pos: i64 = (match dir {
-        Dir::L => state.position - (*count as i64),
-        Dir::R => state.position + (*count as i64),
+        Dir::L => audit::op("position - count", p.checked_sub(count), p.wrapping_sub(count))?,
+        Dir::R => audit::op("position + count", p.checked_add(count), p.wrapping_add(count))?,
     }).rem_euclid(100);
     let zeroes = if pos == 0 { state.zeroes + 1 } else { state.zeroes };
-    State {
+    Ok(State {
         zeroes,
         position: pos
-    }
+    })
 }
 
-fn stepper2(current: State, dir_and_count: &(Dir, usize)) -> State {
+fn stepper2(current: State, dir_and_count: &(Dir, usize)) -> Result<State, String> {
     let (dir, raw_count) = dir_and_count;
     let count = raw_count.rem_euclid(100) as i64;
     let full_rotations = raw_count.div_euclid(100);
@@ -56,27 +60,50 @@ fn stepper2(current: State, dir_and_count: &(Dir, usize)) -> State {
         Dir::R => current.position + count,
     };
     let pos = unbound_pos.rem_euclid(100);
-    let mut zeroes = current.zeroes;
-    zeroes += full_rotations;
+    let mut zeroes = audit::op(
+        "zeroes + full rotations",
+        current.zeroes.checked_add(full_rotations),
+        current.zeroes.wrapping_add(full_rotations))?;
     if current.position != 0 && (
        pos == 0 || unbound_pos != pos
     ) { zeroes += 1 }
-    State {
+    Ok(State {
         zeroes,
         position: pos
+    })
+}
+
+/// Clicks the dial one position at a time.
+fn clicks(steps: &[Step]) -> usize {
+    let mut position = 50_i64;
+    let mut zeroes = 0;
+    for (dir, count) in steps {
+        for _ in 0..*count {
+            let click = match dir { Dir::L => -1, Dir::R => 1 };
+            position = (position + click).rem_euclid(100);
+            if position == 0 { zeroes += 1 }
+        }
     }
+    zeroes
 }
 
-fn run<T>(input: &[Step], stepper: T) -> usize
-where T: Fn(State, &Step) -> State {
-    input.iter().fold(
+fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+    (0..size).map(|_| format!(
+        "{}{}", if rng.chance(50) { "L" } else { "R" }, rng.range(1..=999)
+    )).collect()
+}
+
+fn run<T>(input: &[Step], stepper: T) -> Result<usize, String>
+where T: Fn(State, &Step) -> Result<State, String> {
+    input.iter().try_fold(
         State {zeroes: 0, position: 50},
         stepper
-    ).zeroes
+    ).map(|s| s.zeroes)
 }
 
 impl Day for Day01 {
     fn get_name(&self) -> String { "Day 01".to_string() }
+    fn input_file(&self) -> String { "day01.txt".to_string() }
 
     type Input1 = Vec<(Dir, usize)>;
     type Input2 = Vec<(Dir, usize)>;
@@ -84,32 +111,48 @@ impl Day for Day01 {
     fn input1(&self) -> &Self::Input1 { &self.lines }
     fn input2(&self) -> &Self::Input2 { &self.lines }
 
-    fn step1(&self, input: &Self::Input1) {
-        println!("Step 1: {}", run(input, stepper1));
+    fn step1(&self, input: &Self::Input1) -> Result<String, String> {
+        Ok(run(input, stepper1)?.to_string())
     }
 
-    fn step2(&self, input: &Self::Input2) {
-        println!("Step 2: {}", run(input, stepper2));
+    fn step2(&self, input: &Self::Input2) -> Result<String, String> {
+        Ok(run(input, stepper2)?.to_string())
     }
 
     fn new() -> Self { Day01 { lines: Vec::new() } }
 
-    fn setup(&mut self) -> Result<(), String> {
-        let input = read_lines("input/day01.txt")?;
-        self.lines = input.iter()
+    fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+        generate(rng, size)
+    }
+
+    fn parse(&mut self, lines: Vec<String>) -> Result<(), String> {
+        self.lines = lines.iter()
             .map(parse)
             .collect::<Result<_, _>>()?;
         Ok(())
     }
+
+    fn variants(&self, step: usize) -> &'static [&'static str] {
+        if step == 2 { &["clicks"] } else { &[] }
+    }
+
+    fn variant(&self, step: usize, name: &str) -> Result<String, String> {
+        match (step, name) {
+            (2, "clicks") => Ok(clicks(&self.lines).to_string()),
+            _ => Err(unknown_variant(step, name)),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::difftest;
 
     fn step<T>(stepper: T, pos: i64, dir: Dir, count: usize) -> usize
-    where T: Fn(State, &(Dir, usize)) -> State {
-        stepper(State { position: pos, zeroes: 0}, &(dir, count)).zeroes
+    where T: Fn(State, &(Dir, usize)) -> Result<State, String> {
+        stepper(State { position: pos, zeroes: 0}, &(dir, count))
+            .expect("Shouldn't overflow").zeroes
     }
 
     #[test]
@@ -148,4 +191,22 @@ mod tests {
         assert_eq!(step(stepper2, 5, Dir::R, 495), 5);
         assert_eq!(step(stepper2, 5, Dir::L, 505), 6);
     }
+
+    #[test]
+    fn generated() {
+        let lines = generate(&mut Rng::new(1), 100);
+        assert_eq!(lines.len(), 100);
+        assert!(lines.iter().map(parse).all(|r| r.is_ok()));
+    }
+
+    #[test]
+    fn stepper2_matches_brute_force() {
+        let steps = |lines: &[String]| lines.iter()
+            .map(parse).collect::<Result<Vec<_>, _>>().ok();
+        difftest::check(
+            generate, 10,
+            |lines| run(&steps(lines)?, stepper2).ok(),
+            |lines| Some(clicks(&steps(lines)?)),
+        );
+    }
 }
@@ -1,5 +1,7 @@
+use crate::{cancel, par};
+use crate::num::Num;
 use crate::types::Day;
-use crate::input::read_single_line;
+use crate::rng::Rng;
 
 pub struct Day02 {
     input: Vec<(usize, usize)>
@@ -7,6 +9,7 @@ pub struct Day02 {
 
 impl Day for Day02 {
     fn get_name(&self) -> String { "Day 02".to_string() }
+    fn input_file(&self) -> String { "day02.txt".to_string() }
 
     type Input1 = Vec<(usize, usize)>;
     type Input2 = Vec<(usize, usize)>;
@@ -14,19 +17,25 @@ impl Day for Day02 {
     fn input1(&self) -> &Self::Input1 { &self.input }
     fn input2(&self) -> &Self::Input2 { &self.input }
 
-    fn step1(&self, input: &Self::Input1) {
-        println!("Step 1: {}", sum_matches(is_made_of_halves, input))
+    fn step1(&self, input: &Self::Input1) -> Result<String, String> {
+        Ok(sum_matches(is_made_of_halves, input)?.to_string())
     }
 
-    fn step2(&self, input: &Self::Input2) {
-        println!("Step 2: {}", sum_matches(is_made_of_chunks, input))
+    fn step2(&self, input: &Self::Input2) -> Result<String, String> {
+        Ok(sum_matches(is_made_of_chunks, input)?.to_string())
     }
 
     fn new() -> Self { Day02 { input: Vec::new() } }
 
-    fn setup(&mut self) -> Result<(), String> {
-        let line = read_single_line("input/day02.txt")?;
-        self.input = line
+    fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+        generate(rng, size)
+    }
+
+    fn parse(&mut self, lines: Vec<String>) -> Result<(), String> {
+        let [line] = &lines[..] else {
+            return Err("Expected a single line".to_string())
+        };
+        self.input = line.trim()
             .split(",")
             .map(parse_range)
             .collect::<Result<_, String>>()?;
@@ -45,21 +54,34 @@ fn parse_range(item: &str) -> Result<(usize, usize), String> {
     Ok((l, r))
 }
 
-fn sum_matches<T>(pred: T, ranges: &Vec<(usize, usize)>) -> usize
-where T: Fn(usize) -> bool {
-    let mut sum: usize = 0;
-    for (f, t) in ranges {
+fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+    let ranges = (0..size).map(|_| {
+        let digits = rng.range(1..=10) as u32;
+        let from = rng.range(1..=10_usize.pow(digits));
+        format!("{}-{}", from, from + rng.range(0..=1000))
+    }).collect::<Vec<_>>();
+    vec![ranges.join(",")]
+}
+
+fn sum_matches<T>(pred: T, ranges: &[(usize, usize)]) -> Result<Num, String>
+where T: Fn(usize) -> bool + Sync {
+    par::map(ranges, |(f, t)| {
+        let mut sum = Num::from(0);
         for number in *f..=*t {
+            if number % 0x10000 == 0 {
+                cancel::check(|| format!("{} of {}-{} checked", number - f, f, t))?;
+            }
             if pred(number) {
-                sum += number;
+                sum = sum + Num::from(number);
             }
         }
-    }
-    sum
+        Ok(sum)
+    }).into_iter().sum()
 }
 
 fn is_made_of_halves(number: usize) -> bool {
-    let s = number.ilog10() + 1;  // a "size" of the number
+    let Some(s) = number.checked_ilog10().map(|l| l + 1)  // a "size" of the number
+    else { return false };
     if s.rem_euclid(2) == 1 {
         return false
     }
@@ -88,10 +110,10 @@ mod tests {
 
     #[test]
     fn is_made_of_halves_examples() {
-        assert!(is_made_of_halves(101));
-        assert!(!is_made_of_halves(1010));
-        assert!(!is_made_of_halves(1188511885));
-        assert!(is_made_of_halves(1188511886));
+        assert!(!is_made_of_halves(101));
+        assert!(is_made_of_halves(1010));
+        assert!(is_made_of_halves(1188511885));
+        assert!(!is_made_of_halves(1188511886));
     }
 
     #[test]
@@ -101,4 +123,14 @@ mod tests {
         assert!(is_made_of_chunks(123123123));
         assert!(is_made_of_chunks(11111111));
     }
+
+    #[test]
+    fn generated() {
+        let lines = generate(&mut Rng::new(1), 10);
+        let ranges = lines[0].split(",").map(parse_range)
+            .collect::<Result<Vec<_>, _>>()
+            .expect("Should be parsable");
+        assert_eq!(ranges.len(), 10);
+        assert!(ranges.iter().all(|(l, r)| l <= r));
+    }
 }
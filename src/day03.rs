@@ -1,4 +1,5 @@
-use crate::{input::read_lines, types::Day};
+use crate::{par, rng::Rng, types::Day};
+use crate::certificate::Certificate;
 
 type Bank = Vec<u8>;
 
@@ -11,33 +12,46 @@ impl Day for Day03 {
     type Input2 = Vec<Bank>;
 
     fn get_name(&self) -> String { "Day 03".to_string() }
+    fn input_file(&self) -> String { "day03.txt".to_string() }
 
     fn input1(&self) -> &Self::Input1 { &self.banks }
     fn input2(&self) -> &Self::Input2 { &self.banks }
 
-    fn step1(&self, input: &Self::Input1) {
-        let sum: usize = input.iter()
-            .map(|b| max_power(b, 2))
-            .sum();
-        println!("Step 1: {}", sum)
+    fn step1(&self, input: &Self::Input1) -> Result<String, String> {
+        let sum: usize = par::map(input, |b| max_power(b, 2))
+            .into_iter()
+            .sum::<Result<_, _>>()?;
+        Ok(sum.to_string())
     }
 
-    fn step2(&self, input: &Self::Input2) {
-        let sum: usize = input.iter()
-            .map(|b| max_power(b, 12))
-            .sum();
-        println!("Step 1: {}", sum)
+    fn step2(&self, input: &Self::Input2) -> Result<String, String> {
+        let sum: usize = par::map(input, |b| max_power(b, 12))
+            .into_iter()
+            .sum::<Result<_, _>>()?;
+        Ok(sum.to_string())
     }
 
     fn new() -> Self { Day03 { banks: Vec::new() } }
 
-    fn setup(&mut self) -> Result<(), String> {
-        let lines = read_lines("input/day03.txt")?;
+    fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+        generate(rng, size)
+    }
+
+    fn parse(&mut self, lines: Vec<String>) -> Result<(), String> {
         self.banks = lines.iter()
             .map(|s| parse_bank(s))
             .collect::<Result<_, _>>()?;
         Ok(())
     }
+
+    fn certified(&self, step: usize) -> Result<(String, Option<Certificate>), String> {
+        let of = if step == 1 { 2 } else { 12 };
+        let banks = par::map(&self.banks, |b| picks(b, of))
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        let sum: usize = self.banks.iter().zip(&banks).map(|(b, p)| power(b, p)).sum();
+        Ok((sum.to_string(), Some(Certificate::Digits(banks))))
+    }
 }
 
 fn parse_bank(line: &str) -> Result<Bank, String> {
@@ -50,33 +64,52 @@ fn parse_bank(line: &str) -> Result<Bank, String> {
     }).collect()
 }
 
-fn max_power(bank: &Bank, of: usize) -> usize {
-    let mut acc = 0;
+fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+    (0..size).map(|_| {
+        (0..rng.range(12..=100))
+            .map(|_| char::from(b'0' + rng.range(1..=9) as u8))
+            .collect()
+    }).collect()
+}
+
+fn max_power(bank: &Bank, of: usize) -> Result<usize, String> {
+    Ok(power(bank, &picks(bank, of)?))
+}
+
+/// The number made of the picked digits.
+fn power(bank: &Bank, picks: &[usize]) -> usize {
+    picks.iter().fold(0, |acc, i| 10 * acc + bank[*i] as usize)
+}
+
+/// Indices of the digits making the largest number of `of` digits:
+/// each is the first biggest one leaving enough digits for the rest.
+fn picks(bank: &Bank, of: usize) -> Result<Vec<usize>, String> {
+    let first = bank.len().checked_sub(of)
+        .map(Result::Ok)
+        .unwrap_or(Err(format!("Bank is shorter than {}", of)))?;
+    let mut picks = Vec::with_capacity(of);
     let mut start = 0_usize;
-    let mut max = 0_u8;
-    for stop in (bank.len() - of)..bank.len() {
-        for (i, x) in bank
-            .iter().enumerate().take(stop + 1).skip(start)  // start..=stop
-        {
-            if max < *x {
-                max = *x;
-                start = i + 1;
-            }
-        }
-        acc = 10 * acc + max as usize;
-        max = 0;
+    for stop in first..bank.len() {
+        let best = (start..=stop).rev()
+            .max_by_key(|i| bank[*i])
+            .expect("Should be a non-empty range");
+        picks.push(best);
+        start = best + 1;
     }
-    acc
+    Ok(picks)
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
     use super::*;
+    use crate::difftest;
 
     #[test]
     fn max_power_of_2() {
         fn pwr(line: &str) -> usize {
-            max_power(&parse_bank(&line.to_string()).unwrap(), 2)
+            max_power(&parse_bank(line).unwrap(), 2).unwrap()
         }
         assert_eq!(pwr("987654321111111"), 98);
         assert_eq!(pwr("811111111111119"), 89);
@@ -87,11 +120,50 @@ mod tests {
     #[test]
     fn max_power_of_12() {
         fn pwr(line: &str) -> usize {
-            max_power(&parse_bank(&line.to_string()).unwrap(), 12)
+            max_power(&parse_bank(line).unwrap(), 12).unwrap()
         }
         assert_eq!(pwr("987654321111111"), 987654321111);
         assert_eq!(pwr("811111111111119"), 811111111119);
         assert_eq!(pwr("234234234234278"), 434234234278);
         assert_eq!(pwr("818181911112111"), 888911112111);
     }
+
+    #[test]
+    fn generated() {
+        for line in generate(&mut Rng::new(1), 10) {
+            let bank = parse_bank(&line).expect("Should be parsable");
+            assert_eq!(max_power(&bank, 12).map(|p| p.ilog10()), Ok(11));
+        }
+    }
+
+    /// Tries every digit for every place, remembering the best tails.
+    fn brute_power(
+        bank: &Bank, from: usize, of: usize,
+        memo: &mut HashMap<(usize, usize), Option<usize>>,
+    ) -> Option<usize> {
+        if of == 0 { return Some(0) }
+        if let Some(known) = memo.get(&(from, of)) { return *known }
+        let best = (from..bank.len()).filter_map(|i| {
+            let tail = brute_power(bank, i + 1, of - 1, memo)?;
+            Some(bank[i] as usize * 10_usize.pow(of as u32 - 1) + tail)
+        }).max();
+        memo.insert((from, of), best);
+        best
+    }
+
+    #[test]
+    fn max_power_matches_brute_force() {
+        let banks = |lines: &[String]| lines.iter()
+            .map(|l| parse_bank(l)).collect::<Result<Vec<_>, _>>().ok();
+        for of in [2, 12] {
+            difftest::check(
+                generate, 3,
+                |lines| banks(lines)?.iter()
+                    .map(|b| max_power(b, of).ok()).collect::<Option<Vec<_>>>(),
+                |lines| banks(lines)?.iter()
+                    .map(|b| brute_power(b, 0, of, &mut HashMap::new()))
+                    .collect::<Option<Vec<_>>>(),
+            );
+        }
+    }
 }
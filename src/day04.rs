@@ -1,7 +1,8 @@
 use std::collections::HashSet;
 
-use crate::types::Day;
-use crate::input::read_lines;
+use crate::types::{Day, unknown_query};
+use crate::input::parse_usize;
+use crate::rng::Rng;
 
 type Pos = (usize, usize);
 
@@ -19,27 +20,43 @@ impl Day for Day04 {
     type Input2 = Grid;
 
     fn get_name(&self) -> String { "Day 04".to_string() }
+    fn input_file(&self) -> String { "day04.txt".to_string() }
 
     fn input1(&self) -> &Self::Input1 { &self.grid }
     fn input2(&self) -> &Self::Input2 { &self.grid }
 
-    fn step1(&self, input: &Self::Input1) {
+    fn step1(&self, input: &Self::Input1) -> Result<String, String> {
         let accessable = input.accessible().count();
-        println!("Step 1: {}", accessable);
+        Ok(accessable.to_string())
     }
 
-    fn step2(&self, input: &Self::Input2) {
+    fn step2(&self, input: &Self::Input2) -> Result<String, String> {
         let remaining = input.cells.len() - input.clone().clean().cells.len();
-        println!("Step 2: {}", remaining);
+        Ok(remaining.to_string())
     }
 
     fn new() -> Self { Day04 { grid: Grid::new() } }
 
-    fn setup(&mut self) -> Result<(), String> {
-        let lines = read_lines("input/day04.txt")?;
-        self.grid = Grid::from(lines);
+    fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+        generate(rng, size)
+    }
+
+    fn parse(&mut self, lines: Vec<String>) -> Result<(), String> {
+        self.grid = Grid::from(lines)?;
         Ok(())
     }
+
+    fn queries(&self) -> &'static [&'static str] {
+        &["accessible", "neibs X Y"]
+    }
+
+    fn query(&self, name: &str, args: &[&str]) -> Result<String, String> {
+        match (name, args) {
+            ("accessible", []) => Ok(self.grid.accessible().count().to_string()),
+            ("neibs", [x, y]) => Ok(self.grid.neibs((parse_usize(x)?, parse_usize(y)?)).to_string()),
+            _ => Err(unknown_query(self.queries(), name)),
+        }
+    }
 }
 
 const OFFSETS: &[(isize, isize)] = &[
@@ -51,14 +68,18 @@ const OFFSETS: &[(isize, isize)] = &[
 impl Grid {
     fn new() -> Grid { Grid { cells: HashSet::new() } }
 
-    fn from(lines: Vec<String>) -> Grid {
+    fn from(lines: Vec<String>) -> Result<Grid, String> {
         let mut cells = HashSet::new();
-        lines.iter().enumerate().for_each(|(y, row)| {
-            row.chars().enumerate().for_each(|(x, c)| {
-                if c == '@' { cells.insert((x, y)); };
-            });
-        });
-        Grid { cells }
+        for (y, row) in lines.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                match c {
+                    '@' => { cells.insert((x, y)); },
+                    '.' => {},
+                    _ => return Err(format!("Bad cell: {}", c)),
+                }
+            }
+        }
+        Ok(Grid { cells })
     }
 
     fn neibs(&self, (x, y): Pos) -> usize {
@@ -89,6 +110,12 @@ impl Grid {
     }
 }
 
+fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+    (0..size).map(|_| {
+        (0..size).map(|_| if rng.chance(60) { '@' } else { '.' }).collect()
+    }).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -99,10 +126,18 @@ mod tests {
             "@..".to_string(),
             ".@.".to_string(),
             "@@@".to_string(),
-        ]);
+        ]).expect("Should be parsable");
         assert_eq!(grid.neibs((0, 0)), 1);
         assert_eq!(grid.neibs((1, 1)), 4);
         assert_eq!(grid.neibs((1, 2)), 3);
         assert_eq!(grid.neibs((2, 2)), 2);
     }
+
+    #[test]
+    fn generated() {
+        let mut grid = Grid::from(generate(&mut Rng::new(1), 20))
+            .expect("Should be parsable");
+        assert!(grid.accessible().count() <= grid.cells.len());
+        assert!(grid.clean().cells.len() < 20 * 20);
+    }
 }
@@ -1,10 +1,13 @@
 use std::ops::RangeInclusive;
 
-use crate::types::Day;
-use crate::input::{read_lines,parse_usize};
+use crate::num::Num;
+use crate::types::{Day, unknown_query};
+use crate::input::parse_usize;
+use crate::rng::Rng;
 
 type IdRange = RangeInclusive<usize>;
 
+#[derive(Debug)]
 pub struct DB {
     ranges: Vec<IdRange>,
     ids: Vec<usize>,
@@ -32,29 +35,32 @@ impl DB {
         ).count()
     }
 
-    fn all_possible_fresh_ids(&self) -> usize {
-        let mut v = self.ranges.clone();
+    /// Can be more than a `usize` holds, `0-18446744073709551615` is one more.
+    fn all_possible_fresh_ids(&self) -> Num {
+        let mut v = self.ranges.iter()
+            .filter(|r| !r.is_empty())
+            .cloned()
+            .collect::<Vec<_>>();
         v.sort_by_key(|r| *r.start());
         match v.split_first() {
             None => {
                 eprintln!("Range list shouln'd be empty!");
-                0
+                Num::from(0)
             },
             Some((fst, rs)) => {
                 let mut l = *fst.start();
                 let mut r = *fst.end();
-                let mut acc = 0_usize;
+                let mut acc = Num::from(0);
                 for n in rs {
                     if *n.start() > r {
-                        acc += r - l + 1;
+                        acc = acc + Num::from(r - l) + Num::from(1);
                         l = *n.start();
                         r = *n.end();
                     } else {
                         r = r.max(*n.end());
                     }
                 }
-                acc += r - l + 1;
-                acc
+                acc + Num::from(r - l) + Num::from(1)
             },
         }
     }
@@ -69,6 +75,17 @@ fn parse_range(line: &String) -> Result<IdRange, String> {
     Ok(l..=r)
 }
 
+fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+    let max = 1000 * size.max(1);
+    let mut lines = (0..size).map(|_| {
+        let from = rng.range(1..=max);
+        format!("{}-{}", from, from + rng.range(0..=max / 100))
+    }).collect::<Vec<_>>();
+    lines.push(String::new());
+    lines.extend((0..size).map(|_| rng.range(1..=max).to_string()));
+    lines
+}
+
 pub struct Day05 {
     db: DB
 }
@@ -78,32 +95,53 @@ impl Day for Day05 {
     type Input2 = DB;
 
     fn get_name(&self) -> String { "Day 05".to_string() }
+    fn input_file(&self) -> String { "day05.txt".to_string() }
 
     fn input1(&self) -> &Self::Input1 { &self.db }
     fn input2(&self) -> &Self::Input2 { &self.db }
 
-    fn step1(&self, input: &Self::Input1) {
+    fn step1(&self, input: &Self::Input1) -> Result<String, String> {
         let fresh = input.count_fresh();
-        println!("Step 1: {}", fresh);
+        Ok(fresh.to_string())
     }
 
-    fn step2(&self, input: &Self::Input2) {
+    fn step2(&self, input: &Self::Input2) -> Result<String, String> {
         let fresh = input.all_possible_fresh_ids();
-        println!("Step 2: {}", fresh);
+        Ok(fresh.to_string())
     }
 
     fn new() -> Self { Day05 { db: DB::new() } }
 
-    fn setup(&mut self) -> Result<(), String> {
-        let lines = read_lines("input/day05.txt")?;
+    fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+        generate(rng, size)
+    }
+
+    fn parse(&mut self, lines: Vec<String>) -> Result<(), String> {
         self.db = DB::from(lines)?;
         Ok(())
     }
+
+    fn queries(&self) -> &'static [&'static str] {
+        &["count_fresh", "all_possible_fresh_ids", "is_fresh ID"]
+    }
+
+    fn query(&self, name: &str, args: &[&str]) -> Result<String, String> {
+        match (name, args) {
+            ("count_fresh", []) => Ok(self.db.count_fresh().to_string()),
+            ("all_possible_fresh_ids", []) => Ok(self.db.all_possible_fresh_ids().to_string()),
+            ("is_fresh", [id]) => {
+                let id = parse_usize(id)?;
+                Ok(self.db.ranges.iter().any(|r| r.contains(&id)).to_string())
+            },
+            _ => Err(unknown_query(self.queries(), name)),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{difftest, snapshot};
 
     #[test]
     fn all_possible_fresh_ids() {
@@ -113,6 +151,49 @@ mod tests {
             "16-20".to_string(),
             "12-18".to_string(),
         ]).expect("Should always be Ok");
-        assert_eq!(db.all_possible_fresh_ids(), 14);
+        assert_eq!(db.all_possible_fresh_ids(), Num::from(14));
+        let db = DB::from(vec!["0-18446744073709551615".to_string()]).expect("Should be Ok");
+        assert_eq!(db.all_possible_fresh_ids(), Num::Small(1 << 64));
+    }
+
+    #[test]
+    fn query() {
+        let mut day = Day05::new();
+        day.parse(["3-5", "10-14", "", "5", "8"].map(ToOwned::to_owned).to_vec())
+            .expect("Should be parsable");
+        assert_eq!(day.query("count_fresh", &[]), Ok("1".to_string()));
+        assert_eq!(day.query("all_possible_fresh_ids", &[]), Ok("8".to_string()));
+        assert_eq!(day.query("is_fresh", &["11"]), Ok("true".to_string()));
+        assert_eq!(day.query("is_fresh", &[]), Err("Usage: is_fresh ID".to_string()));
+    }
+
+    #[test]
+    fn parsed_example() {
+        let lines = ["3-5", "10-14", "16-20", "12-18", "", "1", "5", "8", "11", "17", "32"];
+        let db = DB::from(lines.map(ToOwned::to_owned).to_vec()).expect("Should be parsable");
+        snapshot::check("day05_example", &format!("{:#?}\n", db));
+    }
+
+    #[test]
+    fn generated() {
+        let db = DB::from(generate(&mut Rng::new(1), 10))
+            .expect("Should be parsable");
+        assert_eq!(db.ranges.len(), 10);
+        assert_eq!(db.ids.len(), 10);
+    }
+
+    /// Checks every id up to the largest range end.
+    fn brute_fresh_ids(db: &DB) -> usize {
+        let max = db.ranges.iter().map(|r| *r.end()).max().unwrap_or(0);
+        (0..=max).filter(|id| db.ranges.iter().any(|r| r.contains(id))).count()
+    }
+
+    #[test]
+    fn all_possible_fresh_ids_matches_brute_force() {
+        difftest::check(
+            generate, 10,
+            |lines| Some(DB::from(lines.to_vec()).ok()?.all_possible_fresh_ids()),
+            |lines| Some(Num::from(brute_fresh_ids(&DB::from(lines.to_vec()).ok()?))),
+        );
     }
 }
@@ -1,5 +1,7 @@
 use crate::types::Day;
-use crate::input::{read_lines,parse_usize};
+use crate::input::parse_usize;
+use crate::num::Num;
+use crate::rng::Rng;
 
 #[derive(Clone, Copy, Debug)]
 pub enum Op { Mul, Add }
@@ -11,6 +13,7 @@ struct Problem {
     rows: Vec<String>,
 }
 
+#[derive(Debug)]
 pub struct Input {
     problems: Vec<Problem>,
 }
@@ -19,15 +22,17 @@ impl Input {
     fn new() -> Input { Input { problems: Vec::new() } }
 
     fn from(lines: Vec<String>) -> Result<Input, String> {
-        let last_line = lines.last().expect("Input shoudn't be empty");
-        let but_last = &lines[0..lines.len() - 1];
+        let (last_line, but_last) = lines.split_last()
+            .map(Result::Ok)
+            .unwrap_or(Err("Input shoudn't be empty".to_string()))?;
         let ops = last_line
             .chars().enumerate()
             .filter_map(|(i, c)| match c {
-                '+' => Some((i, Op::Add)),
-                '*' => Some((i, Op::Mul)),
-                _ => None,
-            }).collect::<Vec<_>>();
+                '+' => Some(Ok((i, Op::Add))),
+                '*' => Some(Ok((i, Op::Mul))),
+                ' ' => None,
+                _ => Some(Err(format!("Bad operator: {}", c))),
+            }).collect::<Result<Vec<_>, _>>()?;
         let problems = ops.iter()
             .zip(ops.iter().skip(1).map(|(i,_)| i)
                  .chain(vec![&(last_line.len() + 1)])
@@ -45,35 +50,65 @@ impl Input {
         Ok(Input { problems })
     }
 
-    fn grand_total(&self) -> usize {
+    fn grand_total(&self) -> Num {
         self.problems.iter()
-            .map(|p| match p.op {
-                Op::Add => p.numbers.iter().sum::<usize>(),
-                Op::Mul => p.numbers.iter().product(),
-            }).sum()
+            .map(|p| calculate(p.op, &p.numbers))
+            .sum()
     }
 
-    fn grand_total_rtl(&self) -> usize {
+    fn grand_total_rtl(&self) -> Result<Num, String> {
         self.problems.iter()
-            .map(|p| match p.op {
-                Op::Add => rtl(&p.rows).iter().sum::<usize>(),
-                Op::Mul => rtl(&p.rows).iter().product(),
-            }).sum()
+            .map(|p| Ok(calculate(p.op, &rtl(&p.rows)?)))
+            .sum()
+    }
+}
+
+fn calculate(op: Op, numbers: &[usize]) -> Num {
+    let numbers = numbers.iter().map(|n| Num::from(*n));
+    match op {
+        Op::Add => numbers.sum(),
+        Op::Mul => numbers.product(),
     }
 }
 
-fn rtl(rows: &[String]) -> Vec<usize> {
-    let l = rows.first().expect("Shouln't be empty").len();
+fn rtl(rows: &[String]) -> Result<Vec<usize>, String> {
+    let rows = rows.iter()
+        .map(|row| row.chars().collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let l = rows.iter().map(Vec::len).max().unwrap_or(0);
     (0..l).rev()
         .map(|i| {
-            let chars = rows.iter().map(
-                |row| row.chars().nth(i).expect("Should present")
-            );
-            String::from_iter(chars)
-                .trim().parse::<usize>().expect("Shoul always succeed")
+            let chars = rows.iter().filter_map(|row| row.get(i));
+            parse_usize(String::from_iter(chars).trim())
         }).collect()
 }
 
+fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+    const ROWS: usize = 4;
+    let mut lines = vec![String::new(); ROWS + 1];
+    for i in 0..size {
+        // ordered by length so that every column's digits are contiguous
+        let mut numbers = (0..ROWS)
+            .map(|_| rng.range(1..=9999).to_string())
+            .collect::<Vec<_>>();
+        numbers.sort_by_key(String::len);
+        if rng.chance(50) { numbers.reverse() }
+        let width = numbers.iter().map(String::len).max().unwrap_or(1);
+        let left = rng.chance(50);
+        let op = if rng.chance(50) { '+' } else { '*' };
+        if i > 0 { lines.iter_mut().for_each(|l| l.push(' ')) }
+        for (line, n) in lines.iter_mut().zip(&numbers) {
+            line.push_str(&if left {
+                format!("{:<w$}", n, w = width)
+            } else {
+                format!("{:>w$}", n, w = width)
+            });
+        }
+        lines[ROWS].push_str(&format!("{:<w$}", op, w = width));
+    }
+    lines
+}
+
 pub struct Day06 {
     input: Input
 }
@@ -83,22 +118,26 @@ impl Day for Day06 {
     type Input2 = Input;
 
     fn get_name(&self) -> String { "Day 06".to_string() }
+    fn input_file(&self) -> String { "day06.txt".to_string() }
 
     fn input1(&self) -> &Self::Input1 { &self.input }
     fn input2(&self) -> &Self::Input2 { &self.input }
 
-    fn step1(&self, input: &Self::Input1) {
-        println!("Step 1: {}", input.grand_total());
+    fn step1(&self, input: &Self::Input1) -> Result<String, String> {
+        Ok(input.grand_total().to_string())
     }
 
-    fn step2(&self, input: &Self::Input2) {
-        println!("Step 2: {}", input.grand_total_rtl());
+    fn step2(&self, input: &Self::Input2) -> Result<String, String> {
+        Ok(input.grand_total_rtl()?.to_string())
     }
 
     fn new() -> Self { Day06 { input: Input::new() } }
 
-    fn setup(&mut self) -> Result<(), String> {
-        let lines = read_lines("input/day06.txt")?;
+    fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+        generate(rng, size)
+    }
+
+    fn parse(&mut self, lines: Vec<String>) -> Result<(), String> {
         self.input = Input::from(lines)?;
         Ok(())
     }
@@ -107,6 +146,7 @@ impl Day for Day06 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::snapshot;
 
     fn example() -> Input { Input::from(vec![
         "123 328  51 64 ".to_owned(),
@@ -117,18 +157,39 @@ mod tests {
 
     #[test]
     fn grand_total() {
-        assert_eq!(example().grand_total(), 4277556);
+        assert_eq!(example().grand_total(), Num::from(4277556));
     }
 
     #[test]
     fn rtl_example() {
         let ps = &example().problems;
-        assert_eq!(rtl(&ps[0].rows), vec![356, 24, 1]);
-        assert_eq!(rtl(&ps[3].rows), vec![4, 431, 623]);
+        assert_eq!(rtl(&ps[0].rows), Ok(vec![356, 24, 1]));
+        assert_eq!(rtl(&ps[3].rows), Ok(vec![4, 431, 623]));
     }
 
     #[test]
     fn grand_total_rtl() {
-        assert_eq!(example().grand_total_rtl(), 3263827);
+        assert_eq!(example().grand_total_rtl(), Ok(Num::from(3263827)));
+    }
+
+    #[test]
+    fn parsed_example() {
+        snapshot::check("day06_example", &format!("{:#?}\n", example()));
+    }
+
+    #[test]
+    fn generated() {
+        let input = Input::from(generate(&mut Rng::new(1), 10))
+            .expect("Should be parsable");
+        assert_eq!(input.problems.len(), 10);
+        assert!(input.grand_total_rtl().is_ok_and(|t| t != Num::from(0)));
+    }
+
+    #[test]
+    fn grand_total_beyond_usize() {
+        let row = "9999999999".to_owned();
+        let i = Input::from(vec![row.clone(), row.clone(), row, "*         ".to_owned()])
+            .expect("Should be parsable");
+        assert_eq!(i.grand_total().to_string(), "999999999700000000029999999999");
     }
 }
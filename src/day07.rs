@@ -1,6 +1,6 @@
 use std::collections::{HashMap, HashSet};
 
-use crate::{input::read_lines, types::Day};
+use crate::{audit, rng::Rng, types::{Day, unknown_query}};
 
 type Pos = (usize, usize);
 
@@ -17,9 +17,10 @@ impl Input {
     }}
 
     fn from(lines: Vec<String>) -> Result<Input, String> {
-        let start_x = lines
-            .first().expect("Should be at least one line")
-            .find('S').expect("Start should be on the first line");
+        let start_x = lines.first()
+            .and_then(|l| l.find('S'))
+            .map(Result::Ok)
+            .unwrap_or(Err("Start should be on the first line".to_string()))?;
         let start = (start_x, 0_usize);
         let height = lines.len();
         let splitters = lines.iter().enumerate().skip(1).flat_map(
@@ -30,7 +31,7 @@ impl Input {
         Ok(Input { height, start, splitters })
     }
 
-    fn touched_splitters(&self) -> usize {
+    fn touched_splitters(&self) -> Result<usize, String> {
         let mut rays: HashSet<usize> = HashSet::new();
         rays.insert(self.start.0);
         let mut y = 1_usize;
@@ -41,7 +42,7 @@ impl Input {
             for ray in rays {
                 if self.splitters.contains(&(ray, y)) {
                     splits += 1;
-                    new.insert(ray.wrapping_sub(1));
+                    new.insert(left_of(ray)?);
                     new.insert(ray + 1);
                 } else {
                     new.insert(ray);
@@ -49,10 +50,10 @@ impl Input {
             }
             rays = new;
         }
-        splits
+        Ok(splits)
     }
 
-    fn timelines(&self) -> usize {
+    fn timelines(&self) -> Result<usize, String> {
         let mut rays: HashMap<usize, usize> = HashMap::new();
         rays.insert(self.start.0, 1);
         let mut y = 1_usize;
@@ -61,22 +62,44 @@ impl Input {
             let mut new: HashMap<usize, usize> = HashMap::new();
             for (ray, lines) in rays {
                 if self.splitters.contains(&(ray, y)) {
-                    addsert(&mut new, ray.wrapping_sub(1), lines);
-                    addsert(&mut new, ray.wrapping_add(1), lines);
+                    addsert(&mut new, left_of(ray)?, lines)?;
+                    addsert(&mut new, ray.wrapping_add(1), lines)?;
                 } else {
-                    addsert(&mut new, ray, lines);
+                    addsert(&mut new, ray, lines)?;
                 }
             }
             rays = new;
         }
-        rays.values().sum()
+        rays.values().try_fold(0_usize, |acc, v| audit::op(
+            "timelines total", acc.checked_add(*v), acc.wrapping_add(*v)
+        ))
     }
 }
 
 #[inline]
-fn addsert(map: &mut HashMap<usize, usize>, k: usize, v: usize) {
+fn left_of(ray: usize) -> Result<usize, String> {
+    audit::op("ray column - 1", ray.checked_sub(1), ray.wrapping_sub(1))
+}
+
+#[inline]
+fn addsert(map: &mut HashMap<usize, usize>, k: usize, v: usize) -> Result<(), String> {
     let current = *map.get(&k).unwrap_or(&0);
-    map.insert(k, v + current);
+    let sum = audit::op("timelines count", v.checked_add(current), v.wrapping_add(current))?;
+    map.insert(k, sum);
+    Ok(())
+}
+
+fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+    let half = size + 1;
+    let width = 2 * half + 1;
+    let mut lines = vec![format!("{}S{}", ".".repeat(half), ".".repeat(half))];
+    for y in 1..2 * half {
+        lines.push((0..width).map(|x| {
+            let inner = x > 0 && x < width - 1;
+            if y % 2 == 0 && inner && rng.chance(30) { '^' } else { '.' }
+        }).collect());
+    }
+    lines
 }
 
 pub struct Day07 {
@@ -88,30 +111,47 @@ impl Day for Day07 {
     type Input2 = Input;
 
     fn get_name(&self) -> String { "Day 07".to_string() }
+    fn input_file(&self) -> String { "day07.txt".to_string() }
 
     fn input1(&self) -> &Self::Input1 { &self.input }
     fn input2(&self) -> &Self::Input2 { &self.input }
 
-    fn step1(&self, input: &Self::Input1) {
-        println!("Step 1: {}", input.touched_splitters());
+    fn step1(&self, input: &Self::Input1) -> Result<String, String> {
+        Ok(input.touched_splitters()?.to_string())
     }
 
-    fn step2(&self, input: &Self::Input2) {
-        println!("Step 2: {}", input.timelines());
+    fn step2(&self, input: &Self::Input2) -> Result<String, String> {
+        Ok(input.timelines()?.to_string())
     }
 
     fn new() -> Self { Day07 { input: Input::new() } }
 
-    fn setup(&mut self) -> Result<(), String> {
-        let lines = read_lines("input/day07.txt")?;
+    fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+        generate(rng, size)
+    }
+
+    fn parse(&mut self, lines: Vec<String>) -> Result<(), String> {
         self.input = Input::from(lines)?;
         Ok(())
     }
+
+    fn queries(&self) -> &'static [&'static str] {
+        &["touched_splitters", "timelines"]
+    }
+
+    fn query(&self, name: &str, args: &[&str]) -> Result<String, String> {
+        match (name, args) {
+            ("touched_splitters", []) => Ok(self.input.touched_splitters()?.to_string()),
+            ("timelines", []) => Ok(self.input.timelines()?.to_string()),
+            _ => Err(unknown_query(self.queries(), name)),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::difftest;
 
     #[test]
     fn touched_splitters() {
@@ -125,7 +165,7 @@ mod tests {
             "..^.^.^..".to_string(),
             ".........".to_string(),
         ]).expect("Should always work");
-        assert_eq!(i.touched_splitters(), 4);
+        assert_eq!(i.touched_splitters(), Ok(4));
     }
 
     #[test]
@@ -140,6 +180,37 @@ mod tests {
             "..^.^....".to_string(),
             ".1.3.21..".to_string(),
         ]).expect("Should always work");
-        assert_eq!(i.timelines(), 7);
+        assert_eq!(i.timelines(), Ok(7));
+    }
+
+    #[test]
+    fn generated() {
+        let i = Input::from(generate(&mut Rng::new(1), 10))
+            .expect("Should be parsable");
+        assert!(i.timelines().is_ok_and(|t| t >= 1));
+    }
+
+    /// Follows every single timeline down the manifold.
+    fn brute_timelines(i: &Input, ray: usize, y: usize) -> usize {
+        if y >= i.height { return 1 }
+        if i.splitters.contains(&(ray, y)) {
+            brute_timelines(i, ray.wrapping_sub(1), y + 1)
+                + brute_timelines(i, ray + 1, y + 1)
+        } else {
+            brute_timelines(i, ray, y + 1)
+        }
+    }
+
+    #[test]
+    fn timelines_matches_brute_force() {
+        let input = |lines: &[String]| Input::from(lines.to_vec()).ok();
+        difftest::check(
+            generate, 8,
+            |lines| input(lines)?.timelines().ok(),
+            |lines| {
+                let i = input(lines)?;
+                Some(brute_timelines(&i, i.start.0, 1))
+            },
+        );
     }
 }
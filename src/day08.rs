@@ -1,7 +1,10 @@
 use std::collections::{HashMap, HashSet};
 
-use crate::types::Day;
-use crate::input::{parse_usize,read_lines};
+use crate::types::{Day, unknown_query};
+use crate::input::parse_usize;
+use crate::{audit, cancel, par};
+use crate::rng::Rng;
+use crate::certificate::Certificate;
 
 type Jbox = (usize, usize, usize);
 
@@ -27,19 +30,26 @@ impl Input {
         Ok(Input { boxes })
     }
 
-    fn sorted_deltas(&self) -> Vec<(usize, usize, usize)> {
-        let mut result: Vec<(usize, usize, usize)> = Vec::new();
-        for (i, first) in self.boxes.iter().enumerate() {
-            for (j, second) in self.boxes.iter().enumerate().skip(i + 1) {
-                result.push((distance(first, second), i, j));
-            }
-        }
+    fn sorted_deltas(&self) -> Result<Vec<(usize, usize, usize)>, String> {
+        let indices = (0..self.boxes.len()).collect::<Vec<_>>();
+        let rows = par::map(&indices, |&i| {
+            cancel::check(|| format!("{} of {} boxes paired", i, self.boxes.len()))?;
+            let first = &self.boxes[i];
+            self.boxes.iter().enumerate().skip(i + 1)
+                .map(|(j, second)| Ok((squared_distance(first, second)?, i, j)))
+                .collect::<Result<Vec<_>, String>>()
+        });
+        let mut result = Vec::new();
+        for row in rows { result.extend(row?) }
         result.sort_by_key(|(distance, _, _)| *distance);
-        result
+        Ok(result)
     }
 
-    fn circuits_after_joins(&self, limit: usize) -> usize {
-        let deltas = self.sorted_deltas();
+    /// The answer along with the pairs joined on the way to it.
+    fn circuits_after_joins(
+        &self, limit: usize
+    ) -> Result<(usize, Vec<(usize, usize)>), String> {
+        let deltas = self.sorted_deltas()?;
         let mut circuits: HashMap<usize, HashSet<usize>> = HashMap::new();
         let mut jbox2circuit: HashMap<usize, usize> = HashMap::new();
         for i in 0..self.boxes.len() {
@@ -50,7 +60,9 @@ impl Input {
         }
         let source = deltas.iter()
             .take(if limit > 0 { limit } else { deltas.len() });
+        let mut joins = Vec::new();
         for (_, a, b) in source {
+            joins.push((*a, *b));
             let ca = *jbox2circuit.get(a).expect("Impossible");
             let cb = *jbox2circuit.get(b).expect("Impossible");
             if ca != cb {
@@ -66,28 +78,49 @@ impl Input {
                         .expect("Should present");
                     let bb = self.boxes.get(*b)
                         .expect("Should present");
-                    return ba.0 * bb.0
+                    let x = audit::op(
+                        "product of xs", ba.0.checked_mul(bb.0), ba.0.wrapping_mul(bb.0)
+                    )?;
+                    return Ok((x, joins))
                 }
             }
         }
         let mut sizes = circuits.values().map(|s| s.len()).collect::<Vec<_>>();
         sizes.sort_by_key(|x| -(*x as isize));
-        sizes.iter().take(3).product()
+        let product = sizes.iter().take(3).try_fold(1_usize, |acc, s| audit::op(
+            "product of sizes", acc.checked_mul(*s), acc.wrapping_mul(*s)
+        ))?;
+        Ok((product, joins))
     }
 }
 
-fn distance(first: &Jbox, second: &Jbox) -> usize {
+fn squared_distance(first: &Jbox, second: &Jbox) -> Result<usize, String> {
     let (x1, y1, z1) = first;
     let (x2, y2, z2) = second;
-    let dx = square_of_difference(x1, x2);
-    let dy = square_of_difference(y1, y2);
-    let dz = square_of_difference(z1 ,z2);
-    (dx + dy + dz).isqrt()
+    let dx = square_of_difference(x1, x2)?;
+    let dy = square_of_difference(y1, y2)?;
+    let dz = square_of_difference(z1 ,z2)?;
+    let sum = [dx, dy, dz].iter().try_fold(0_usize, |acc, d| audit::op(
+        "sum of squares", acc.checked_add(*d), acc.wrapping_add(*d)
+    ))?;
+    Ok(sum)
 }
 
 #[inline]
-fn square_of_difference(a: &usize, b: &usize) -> usize {
-    (*a as isize - *b as isize).pow(2) as usize
+fn square_of_difference(a: &usize, b: &usize) -> Result<usize, String> {
+    let cast = |v: &usize| audit::op(
+        "coordinate as isize", isize::try_from(*v).ok(), *v as isize);
+    let (a, b) = (cast(a)?, cast(b)?);
+    let d = audit::op("coordinate difference", a.checked_sub(b), a.wrapping_sub(b))?;
+    let square = audit::op("difference squared", d.checked_pow(2), d.wrapping_pow(2))?;
+    Ok(square as usize)
+}
+
+fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+    (0..size).map(|_| format!(
+        "{},{},{}",
+        rng.range(0..=99999), rng.range(0..=99999), rng.range(0..=99999)
+    )).collect()
 }
 
 pub struct Day08 {
@@ -99,25 +132,84 @@ impl Day for Day08 {
     type Input2 = Input;
 
     fn get_name(&self) -> String { "Day 08".to_string() }
+    fn input_file(&self) -> String { "day08.txt".to_string() }
 
     fn input1(&self) -> &Self::Input1 { &self.input }
     fn input2(&self) -> &Self::Input2 { &self.input }
 
-    fn step1(&self, input: &Self::Input1) {
-        let result = input.circuits_after_joins(1000);
-        println!("Step 1: {}", result);
+    fn step1(&self, input: &Self::Input1) -> Result<String, String> {
+        let (result, _) = input.circuits_after_joins(1000)?;
+        Ok(result.to_string())
     }
 
-    fn step2(&self, input: &Self::Input2) {
-        let result = input.circuits_after_joins(0);
-        println!("Step 2: {}", result);
+    fn step2(&self, input: &Self::Input2) -> Result<String, String> {
+        let (result, _) = input.circuits_after_joins(0)?;
+        Ok(result.to_string())
     }
 
     fn new() -> Self { Day08 { input: Input::new() } }
 
-    fn setup(&mut self) -> Result<(), String> {
-        let lines = read_lines("input/day08.txt")?;
+    fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+        generate(rng, size)
+    }
+
+    fn parse(&mut self, lines: Vec<String>) -> Result<(), String> {
         self.input = Input::from(lines)?;
         Ok(())
     }
+
+    fn certified(&self, step: usize) -> Result<(String, Option<Certificate>), String> {
+        let limit = if step == 1 { 1000 } else { 0 };
+        let (result, joins) = self.input.circuits_after_joins(limit)?;
+        Ok((result.to_string(), Some(Certificate::Joins(joins))))
+    }
+
+    fn queries(&self) -> &'static [&'static str] {
+        &["circuits_after_joins LIMIT", "squared_distance BOX BOX"]
+    }
+
+    fn query(&self, name: &str, args: &[&str]) -> Result<String, String> {
+        match (name, args) {
+            ("circuits_after_joins", [limit]) => {
+                let (result, _) = self.input.circuits_after_joins(parse_usize(limit)?)?;
+                Ok(result.to_string())
+            },
+            ("squared_distance", [a, b]) => {
+                let jbox = |i: &str| self.input.boxes.get(parse_usize(i)?)
+                    .map(Result::Ok)
+                    .unwrap_or(Err(format!("No box {}", i)));
+                Ok(squared_distance(jbox(a)?, jbox(b)?)?.to_string())
+            },
+            _ => Err(unknown_query(self.queries(), name)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sorted_by_exact_distance() {
+        // sqrt(8) and sqrt(4) both round down to 2
+        let i = Input::from(["0,0,0", "2,2,0", "0,0,2"].map(ToOwned::to_owned).to_vec())
+            .expect("Should be parsable");
+        assert_eq!(i.sorted_deltas().expect("Should fit")[..2], [(4, 0, 2), (8, 0, 1)]);
+    }
+
+    #[test]
+    fn generated() {
+        let i = Input::from(generate(&mut Rng::new(1), 20))
+            .expect("Should be parsable");
+        assert_eq!(i.boxes.len(), 20);
+        assert!(i.circuits_after_joins(0).is_ok());
+    }
+
+    #[test]
+    fn huge_coordinates() {
+        // the product of the last join's xs wraps without the audit mode
+        let i = Input::from(["4294967296,0,0", "4294967296,0,0"].map(ToOwned::to_owned).to_vec())
+            .expect("Should be parsable");
+        assert_eq!(i.circuits_after_joins(0).map(|(x, _)| x), Ok(0));
+    }
 }
@@ -5,9 +5,12 @@
 
 use std::collections::HashMap;
 
-use crate::{input::{parse_usize, read_lines}, types::Day};
+use crate::{input::parse_usize, audit, cancel, par, rng::Rng, types::{Day, solve}};
+use crate::certificate::Certificate;
 
 type Pos = (usize, usize);
+/// An area with the corners spanning it.
+type Spanned = (usize, Pos, Pos);
 
 pub struct Input {
     tiles: Vec<Pos>
@@ -28,15 +31,34 @@ impl Input {
         Ok(Input { tiles })
     }
 
-    fn largest_rectangle(&self) -> usize {
-        let mut max = 0_usize;
-        for (i, p1) in self.tiles.iter().enumerate() {
+    /// The area of the largest rectangle and its corners. Once cancelled,
+    /// the rows not started yet are skipped and the best of the finished
+    /// ones is reported.
+    fn largest_rectangle(&self) -> Result<Option<Spanned>, String> {
+        let indices = (0..self.tiles.len()).collect::<Vec<_>>();
+        let rows = par::map(&indices, |&i| -> Option<Result<Option<Spanned>, String>> {
+            cancel::check(String::new).ok()?;
+            let p1 = self.tiles[i];
+            let mut best = None;
             for p2 in self.tiles.iter().skip(i + 1) {
-                let m = Rect::from(p1, p2).area();
-                if m > max { max = m }
+                let area = match Rect::from(&p1, p2).area() {
+                    Ok(area) => area,
+                    Err(e) => return Some(Err(e)),
+                };
+                if best.is_none_or(|(max, _, _)| area > max) { best = Some((area, p1, *p2)) }
             }
-        };
-        max
+            Some(Ok(best))
+        });
+        let (mut best, mut paired): (Option<Spanned>, usize) = (None, 0);
+        for row in rows.into_iter().flatten() {
+            paired += 1;
+            if let Some(r) = row? && best.is_none_or(|(max, _, _)| r.0 > max) { best = Some(r) }
+        }
+        if paired < self.tiles.len() {
+            cancel::check(|| format!("{} of {} tiles paired, best area {}",
+                                     paired, self.tiles.len(), best.map_or(0, |(max, _, _)| max)))?;
+        }
+        Ok(best)
     }
 }
 
@@ -60,8 +82,11 @@ impl Rect {
         *px > self.x1 && *px < self.x2 && *py > self.y1 && *py < self.y2
     }
 
-    fn area(&self) -> usize {
-        (1 + self.x2 - self.x1) * (1 + self.y2 - self.y1)
+    fn area(&self) -> Result<usize, String> {
+        let (dx, dy) = (self.x2 - self.x1, self.y2 - self.y1);
+        let w = audit::op("Rect::area width", dx.checked_add(1), dx.wrapping_add(1))?;
+        let h = audit::op("Rect::area height", dy.checked_add(1), dy.wrapping_add(1))?;
+        audit::op("Rect::area", w.checked_mul(h), w.wrapping_mul(h))
     }
 }
 
@@ -133,6 +158,25 @@ impl Scale {
     }
 }
 
+/// A "skyline" polygon: columns of distinct heights standing on a common
+/// bottom edge, listed corner by corner.
+fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+    const BOTTOM: usize = 100_000;
+    let mut x = rng.range(0..=1000);
+    let mut points = vec![(x, BOTTOM)];
+    let mut previous = BOTTOM;
+    for _ in 0..size.max(1) {
+        let mut height = rng.range(0..=BOTTOM - 1);
+        while height == previous { height = rng.range(0..=BOTTOM - 1) }
+        points.push((x, height));
+        x += rng.range(1..=1000);
+        points.push((x, height));
+        previous = height;
+    }
+    points.push((x, BOTTOM));
+    points.iter().map(|(x, y)| format!("{},{}", x, y)).collect()
+}
+
 pub struct Day09 {
     input: Input
 }
@@ -142,30 +186,63 @@ impl Day for Day09 {
     type Input2 = Input;
 
     fn get_name(&self) -> String { "Day 09".to_string() }
+    fn input_file(&self) -> String { "day09.txt".to_string() }
 
     fn input1(&self) -> &Self::Input1 { &self.input }
     fn input2(&self) -> &Self::Input2 { &self.input }
 
-    fn step1(&self, input: &Self::Input1) {
-        let result = input.largest_rectangle();
-        println!("Step 1: {}", result);
+    fn step1(&self, input: &Self::Input1) -> Result<String, String> {
+        let result = input.largest_rectangle()?.map_or(0, |(area, _, _)| area);
+        Ok(result.to_string())
     }
 
-    fn step2(&self, input: &Self::Input2) {
-        let mut v = input.tiles.iter().map(|(x, _)| x).collect::<Vec<_>>();
-        println!("{}", v.len());
-        v = input.tiles.iter().map(|(_, y)| y).collect::<Vec<_>>();
-        println!("{}", v.len());
-        println!("Step 2: {}", "TODO");
+    fn step2(&self, _input: &Self::Input2) -> Result<String, String> {
+        Ok("TODO".to_string())
     }
 
     fn new() -> Self { Day09 { input: Input::new() } }
 
-    fn setup(&mut self) -> Result<(), String> {
-        let lines = read_lines("input/day09.txt")?;
+    fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+        generate(rng, size)
+    }
+
+    fn parse(&mut self, lines: Vec<String>) -> Result<(), String> {
         self.input = Input::from(lines)?;
         Ok(())
     }
+
+    fn certified(&self, step: usize) -> Result<(String, Option<Certificate>), String> {
+        if step != 1 { return Ok((solve(self, step, None)?, None)) }
+        let best = self.input.largest_rectangle()?;
+        let area = best.map_or(0, |(area, _, _)| area);
+        Ok((area.to_string(), best.map(|(_, a, b)| Certificate::Corners(a, b))))
+    }
+
+    /// The floor outline with the largest rectangle on top.
+    fn svg(&self) -> Option<String> {
+        let tiles = &self.input.tiles;
+        let (min_x, max_x) = (tiles.iter().map(|t| t.0).min()?, tiles.iter().map(|t| t.0).max()?);
+        let (min_y, max_y) = (tiles.iter().map(|t| t.1).min()?, tiles.iter().map(|t| t.1).max()?);
+        let points = tiles.iter()
+            .map(|(x, y)| format!("{},{}", x, y))
+            .collect::<Vec<_>>().join(" ");
+        let rect = match self.input.largest_rectangle() {
+            Ok(Some((_, a, b))) => {
+                let r = Rect::from(&a, &b);
+                format!(
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" \
+                     stroke=\"#c22\" stroke-width=\"2\" vector-effect=\"non-scaling-stroke\"/>",
+                    r.x1, r.y1, r.x2 - r.x1, r.y2 - r.y1
+                )
+            },
+            _ => String::new(),
+        };
+        Some(format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\" width=\"400\" height=\"400\">\
+             <polygon points=\"{}\" fill=\"#2a7\"/>{}</svg>",
+            min_x, min_y, (max_x - min_x).max(1), (max_y - min_y).max(1), points, rect
+        ))
+    }
 }
 
 #[cfg(test)]
@@ -187,6 +264,12 @@ mod tests {
         ])
     }
 
+    #[test]
+    fn huge_area() {
+        // wraps without the audit mode
+        assert_eq!(Rect::from(&(0, 0), &(usize::MAX, 0)).area(), Ok(0));
+    }
+
     #[test]
     fn scaling() {
         let s = Scale::from([200, 1_usize, 5, 200, 1000].iter());
@@ -194,4 +277,22 @@ mod tests {
         assert_eq!(s.up(6), 1000);
         assert_eq!(s.up(s.down(200)), 200);
     }
+
+    #[test]
+    fn cancelled_search() {
+        let i = Input::from(generate(&mut Rng::new(1), 10))
+            .expect("Should be parsable");
+        let token = cancel::Token::new(None);
+        token.cancel();
+        let result = cancel::scope(&token, || i.largest_rectangle());
+        assert_eq!(result, Err("Cancelled: 0 of 22 tiles paired, best area 0".to_string()));
+    }
+
+    #[test]
+    fn generated() {
+        let i = Input::from(generate(&mut Rng::new(1), 10))
+            .expect("Should be parsable");
+        assert_eq!(i.tiles.len(), 22);
+        assert_eq!(Path::from(i.tiles).steps.len(), 22);
+    }
 }
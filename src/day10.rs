@@ -1,13 +1,16 @@
-use crate::input::read_lines;
-use crate::types::Day;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::{cancel, par, progress};
+use crate::rng::Rng;
+use crate::types::{Day, unknown_variant};
+use crate::certificate::Certificate;
 
 extern crate combine;
 use combine::{between, eof, many1, one_of, sep_by1, EasyParser, Parser};
 use combine::parser::char::{spaces,digit,char};
 
-extern crate pathfinding;
-use pathfinding::directed::bfs::bfs;
-
+#[derive(Debug)]
 struct Machine {
     target: u16,
     buttons: Vec<Vec<u16>>,
@@ -19,45 +22,136 @@ impl Machine {
         let number = many1(digit()).and_then(|s: String| s.parse::<u16>());
         let numbers = sep_by1::<Vec<u16>, _, _, _>(number, char(','));
         let led_p = one_of(".#".chars());
-        let target_p = between(char('['), char(']'), many1(led_p).map(leds_to_u16));
+        let target_p = between(char('['), char(']'), many1::<Vec<char>, _, _>(led_p));
         let button_p = between(char('('), char(')'), numbers.clone());
         let joltages_p = between( char('{'), char('}'), numbers);
         let mut line_p = (
             target_p,
             spaces(),
-            many1(button_p.skip(spaces())),
+            many1::<Vec<Vec<u16>>, _, _>(button_p.skip(spaces())),
             spaces(),
             joltages_p,
             eof()
-        ).map(|(target, _, buttons, _, joltages, _)|
-              Machine { target, buttons, joltages }
-        );
-        line_p.easy_parse(line)
+        ).map(|(leds, _, buttons, _, joltages, _)| (leds, buttons, joltages));
+        let (leds, buttons, joltages) = line_p.easy_parse(line)
             .map(|(m, _)| m)
-            .map_err(|e| e.to_string())
+            .map_err(|e| e.to_string())?;
+        if leds.len() > 16 {
+            return Err(format!("Too many lights: {}", line))
+        }
+        if buttons.iter().flatten().any(|i| *i >= 16) {
+            return Err(format!("Button wired to a missing light: {}", line))
+        }
+        Ok(Machine { target: leds_to_u16(leds), buttons, joltages })
+    }
+
+    fn fewest_presses_to_init(&self) -> Result<usize, String> {
+        Ok(self.presses_to_init()?.iter().sum())
     }
 
-    fn fewest_presses_to_init(&self) -> usize {
+    fn fewest_presses_to_power(&self) -> Result<usize, String> {
+        Ok(self.presses_to_power()?.iter().sum())
+    }
+
+    /// How many times each button is pressed on the shortest way to the target.
+    fn presses_to_init(&self) -> Result<Vec<usize>, String> {
         let bs = self.buttons.iter().map(button_to_bits).collect::<Vec<u16>>();
-        let search = bfs(&0_u16, |&v| {
+        let path = search(0_u16, |&v| {
             bs.iter().map(|b| v ^ *b).collect::<Vec<_>>()
-        }, |s| { *s == self.target }
-        ).expect("Should be always possible");
-        search.len() - 1 // without the start point
+        }, |s| { *s == self.target })?;
+        Ok(self.count_presses(&path, |from, to| bs.iter().position(|b| from ^ b == *to)))
     }
 
-    fn fewest_presses_to_power(&self) -> usize {
-        println!("{:?}", self.joltages);
+    /// Pressing a button twice undoes it, so the fewest presses is the
+    /// smallest set of buttons toggling exactly the target lights.
+    fn fewest_presses_by_subsets(&self) -> Result<usize, String> {
+        let bs = self.buttons.iter().map(button_to_bits).collect::<Vec<u16>>();
+        if bs.len() > 20 {
+            return Err(format!("Too many buttons to try every set: {}", bs.len()))
+        }
+        (0_u32..1 << bs.len())
+            .filter(|set| bs.iter().enumerate()
+                .filter(|(i, _)| set >> i & 1 == 1)
+                .fold(0, |lights, (_, b)| lights ^ b) == self.target)
+            .map(|set| set.count_ones() as usize)
+            .min()
+            .map(Result::Ok)
+            .unwrap_or(Err("No set of buttons reaches the target".to_string()))
+    }
+
+    /// How many times each button is pressed on the shortest way to the joltages.
+    fn presses_to_power(&self) -> Result<Vec<usize>, String> {
+        if self.buttons.iter().flatten().any(|i| *i as usize >= self.joltages.len()) {
+            return Err("Button wired to a missing counter".to_string())
+        }
         let mut start: Vec<u16> = Vec::new();
         (0..self.joltages.len()).for_each(|_| start.push(0_u16));
-        let search = bfs(&start, |current| {
+        let path = search(start, |current| {
             self.buttons.iter().filter_map(
                 |button| press_button(&self.joltages, current, button)
             ).collect::<Vec<_>>()
-        }, |s| { *s == self.joltages }
-        ).expect("Should be always possible");
-        search.len() - 1 // without the start point
+        }, |s| { *s == self.joltages })?;
+        Ok(self.count_presses(&path, |from, to| self.buttons.iter().position(
+            |button| press_button(&self.joltages, from, button).as_ref() == Some(to)
+        )))
     }
+
+    /// Tells which button makes each move of the path.
+    fn count_presses<N, F>(&self, path: &[N], button: F) -> Vec<usize>
+    where F: Fn(&N, &N) -> Option<usize> {
+        let mut counts = vec![0; self.buttons.len()];
+        for step in path.windows(2) {
+            let b = button(&step[0], &step[1]).expect("Search moves by buttons");
+            counts[b] += 1;
+        }
+        counts
+    }
+}
+
+/// Layer by layer BFS, returns the path to the first successful node.
+/// Stops with the explored states count and the reached depth on cancellation.
+fn search<N, FN, FS>(
+    start: N, successors: FN, success: FS
+) -> Result<Vec<N>, String>
+where
+    N: Eq + Hash + Clone,
+    FN: Fn(&N) -> Vec<N>,
+    FS: Fn(&N) -> bool,
+{
+    // every node seen so far, mapped to the one it was reached from
+    let mut seen: HashMap<N, N> = HashMap::new();
+    seen.insert(start.clone(), start.clone());
+    let mut layer = vec![start];
+    let mut depth = 0_usize;
+    while !layer.is_empty() {
+        progress::frontier(layer.len(), depth);
+        let mut next = Vec::new();
+        for node in &layer {
+            if success(node) { return Ok(path_to(&seen, node)) }
+            cancel::check(|| format!(
+                "{} states explored, depth {} reached", seen.len(), depth
+            ))?;
+            for n in successors(node) {
+                if !seen.contains_key(&n) {
+                    seen.insert(n.clone(), node.clone());
+                    next.push(n);
+                }
+            }
+        }
+        layer = next;
+        depth += 1;
+    }
+    Err(format!("Unreachable after {} states explored", seen.len()))
+}
+
+fn path_to<N: Eq + Hash + Clone>(parents: &HashMap<N, N>, node: &N) -> Vec<N> {
+    let mut path = vec![node.clone()];
+    while let Some(parent) = parents.get(path.last().expect("Never empty")) {
+        if parent == path.last().expect("Never empty") { break }
+        path.push(parent.clone());
+    }
+    path.reverse();
+    path
 }
 
 #[inline]
@@ -86,6 +180,37 @@ fn press_button(
     Some(out)
 }
 
+/// Machines with targets and joltages made of random button presses,
+/// so both are always reachable.
+fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+    (0..size).map(|_| {
+        let lights = rng.range(3..=6);
+        let buttons = (0..rng.range(2..=lights + 2)).map(|_| {
+            let mut b = (0..lights).filter(|_| rng.chance(40)).collect::<Vec<_>>();
+            if b.is_empty() { b.push(rng.range(0..=lights - 1)) }
+            b
+        }).collect::<Vec<_>>();
+        let mut leds = vec!['.'; lights];
+        let mut joltages = vec![0_usize; lights];
+        for button in &buttons {
+            let presses = rng.range(0..=3);
+            for i in button {
+                joltages[*i] += presses;
+                if presses % 2 == 1 {
+                    leds[*i] = if leds[*i] == '#' { '.' } else { '#' };
+                }
+            }
+        }
+        let join = |xs: &[usize]| xs.iter()
+            .map(ToString::to_string).collect::<Vec<_>>().join(",");
+        let buttons = buttons.iter()
+            .map(|b| format!("({})", join(b)))
+            .collect::<Vec<_>>().join(" ");
+        format!("[{}] {} {{{}}}", String::from_iter(leds), buttons, join(&joltages))
+    }).collect()
+}
+
+#[derive(Debug)]
 pub struct Input {
     machines: Vec<Machine>
 }
@@ -100,12 +225,16 @@ impl Input {
         Ok(Input { machines })
     }
 
-    fn fewest_presses_to_init(&self) -> usize {
-        self.machines.iter().map(|m| m.fewest_presses_to_init()).sum()
+    fn fewest_presses_to_init(&self) -> Result<usize, String> {
+        par::map(&self.machines, Machine::fewest_presses_to_init).into_iter().sum()
     }
 
-    fn fewest_presses_to_power(&self) -> usize {
-        self.machines.iter().map(|m| m.fewest_presses_to_power()).sum()
+    fn fewest_presses_to_power(&self) -> Result<usize, String> {
+        par::map(&self.machines, Machine::fewest_presses_to_power).into_iter().sum()
+    }
+
+    fn fewest_presses_by_subsets(&self) -> Result<usize, String> {
+        par::map(&self.machines, Machine::fewest_presses_by_subsets).into_iter().sum()
     }
 }
 
@@ -118,30 +247,54 @@ impl Day for Day10 {
     type Input2 = Input;
 
     fn get_name(&self) -> String { "Day 10".to_string() }
+    fn input_file(&self) -> String { "day10_example.txt".to_string() }
 
     fn input1(&self) -> &Self::Input1 { &self.input }
     fn input2(&self) -> &Self::Input2 { &self.input }
 
-    fn step1(&self, input: &Self::Input1) {
-        println!("Step 1: {}", input.fewest_presses_to_init());
+    fn step1(&self, input: &Self::Input1) -> Result<String, String> {
+        Ok(input.fewest_presses_to_init()?.to_string())
     }
 
-    fn step2(&self, input: &Self::Input2) {
-        println!("Step 2: {}", input.fewest_presses_to_power());
+    fn step2(&self, input: &Self::Input2) -> Result<String, String> {
+        Ok(input.fewest_presses_to_power()?.to_string())
     }
 
     fn new() -> Self { Day10 { input: Input::new() } }
 
-    fn setup(&mut self) -> Result<(), String> {
-        let lines = read_lines("input/day10_example.txt")?;
+    fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+        generate(rng, size)
+    }
+
+    fn parse(&mut self, lines: Vec<String>) -> Result<(), String> {
         self.input = Input::from(lines)?;
         Ok(())
     }
+
+    fn certified(&self, step: usize) -> Result<(String, Option<Certificate>), String> {
+        let presses = par::map(&self.input.machines, |m| {
+            if step == 1 { m.presses_to_init() } else { m.presses_to_power() }
+        }).into_iter().collect::<Result<Vec<_>, _>>()?;
+        let total = presses.iter().flatten().sum::<usize>();
+        Ok((total.to_string(), Some(Certificate::Presses(presses))))
+    }
+
+    fn variants(&self, step: usize) -> &'static [&'static str] {
+        if step == 1 { &["subsets"] } else { &[] }
+    }
+
+    fn variant(&self, step: usize, name: &str) -> Result<String, String> {
+        match (step, name) {
+            (1, "subsets") => Ok(self.input.fewest_presses_by_subsets()?.to_string()),
+            _ => Err(unknown_variant(step, name)),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::snapshot;
 
     #[test]
     fn machine_from() {
@@ -151,4 +304,44 @@ mod tests {
         assert_eq!(m.buttons, vec![vec![0, 1], vec![1, 2]]);
         assert_eq!(m.joltages, vec![4, 5]);
     }
+
+    #[test]
+    fn fewest_presses() {
+        let m = Machine::from("[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}")
+            .expect("Should be parsable");
+        assert_eq!(m.fewest_presses_to_init(), Ok(2));
+        assert_eq!(m.fewest_presses_by_subsets(), Ok(2));
+        assert_eq!(m.fewest_presses_to_power(), Ok(10));
+    }
+
+    #[test]
+    fn cancelled_search() {
+        let m = Machine::from("[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}")
+            .expect("Should be parsable");
+        let token = cancel::Token::new(None);
+        token.cancel();
+        let result = cancel::scope(&token, || m.fewest_presses_to_power());
+        assert_eq!(
+            result,
+            Err("Cancelled: 1 states explored, depth 0 reached".to_string())
+        );
+    }
+
+    #[test]
+    fn parsed_example() {
+        let i = Input::from([
+            "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}",
+            "[...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}",
+            "[.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}",
+        ].map(ToOwned::to_owned).to_vec()).expect("Should be parsable");
+        snapshot::check("day10_example", &format!("{:#?}\n", i));
+    }
+
+    #[test]
+    fn generated() {
+        let i = Input::from(generate(&mut Rng::new(1), 5))
+            .expect("Should be parsable");
+        assert!(i.fewest_presses_to_init().is_ok());
+        assert!(i.fewest_presses_to_power().is_ok());
+    }
 }
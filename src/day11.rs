@@ -1,9 +1,7 @@
 use std::collections::HashMap;
 
-extern crate pathfinding;
-use pathfinding::directed::count_paths::count_paths;
-
-use crate::{input::read_lines, types::Day};
+use crate::{num::Num, rng::Rng};
+use crate::types::{Day, unknown_query};
 
 pub struct Input {
     network: HashMap<String, Vec<String>>,
@@ -24,17 +22,85 @@ impl Input {
                 from,words.map(ToOwned::to_owned).collect::<Vec<_>>()
             );
         }
+        if let Some(device) = new.find_loop() {
+            return Err(format!("Network has a loop through {}", device))
+        }
         Ok(new)
     }
 
-    fn count_paths(&self, from: &str, to: &str) -> usize {
-        let empty = Vec::new();
-        count_paths(
-            &from.to_string(),
-            |&current| self.network.get(current).unwrap_or(&empty).iter(),
-            |&current| current.as_str() == to
-        )
+    /// Peels off devices without inputs (Kahn's algorithm),
+    /// anything left is on a loop.
+    fn find_loop(&self) -> Option<&String> {
+        let mut inputs: HashMap<&String, usize> = HashMap::new();
+        for (from, outputs) in &self.network {
+            inputs.entry(from).or_default();
+            for to in outputs { *inputs.entry(to).or_default() += 1 }
+        }
+        let mut free = inputs.iter()
+            .filter(|(_, n)| **n == 0)
+            .map(|(d, _)| *d)
+            .collect::<Vec<_>>();
+        while let Some(device) = free.pop() {
+            inputs.remove(device);
+            for to in self.network.get(device).into_iter().flatten() {
+                let n = inputs.get_mut(to).expect("Should be counted");
+                *n -= 1;
+                if *n == 0 { free.push(to) }
+            }
+        }
+        inputs.into_keys().min()
+    }
+
+    /// Paths counted per device from the outputs back, deepest first.
+    /// The network has no loops, so every device is done after its outputs.
+    fn count_paths(&self, from: &str, to: &str) -> Num {
+        let mut counts: HashMap<&str, Num> = HashMap::new();
+        let mut stack = vec![(from, false)];
+        while let Some((device, expanded)) = stack.pop() {
+            if counts.contains_key(device) { continue }
+            if device == to {
+                counts.insert(device, Num::from(1));
+                continue
+            }
+            let outputs = self.network.get(device).map_or(&[][..], Vec::as_slice);
+            if expanded {
+                let paths = outputs.iter().map(|o| counts[o.as_str()].clone()).sum();
+                counts.insert(device, paths);
+            } else {
+                stack.push((device, true));
+                stack.extend(outputs.iter().map(|o| (o.as_str(), false)));
+            }
+        }
+        counts.remove(from).unwrap_or(Num::from(0))
+    }
+}
+
+const SPECIAL: &[&str] = &["svr", "you", "dac", "fft", "out"];
+
+/// A random DAG over `size` devices, "out" is always the last one.
+fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+    let mut names = (0..)
+        .map(|i: usize| (0..3).rev()
+             .map(|d| char::from(b'a' + (i / 26_usize.pow(d) % 26) as u8))
+             .collect::<String>())
+        .filter(|n| !SPECIAL.contains(&n.as_str()))
+        .take(size.saturating_sub(SPECIAL.len()))
+        .collect::<Vec<_>>();
+    for special in ["dac", "fft"] {
+        let i = rng.range(0..=names.len());
+        names.insert(i, special.to_string());
     }
+    names.insert(0, "you".to_string());
+    names.insert(0, "svr".to_string());
+    names.push("out".to_string());
+    (0..names.len() - 1).map(|i| {
+        let mut outputs = (0..rng.range(1..=3))
+            .map(|_| names[rng.range(i + 1..=names.len() - 1)].as_str())
+            .collect::<Vec<_>>();
+        outputs.sort();
+        outputs.dedup();
+        format!("{}: {}", names[i], outputs.join(" "))
+    }).collect()
 }
 
 pub struct Day11 {
@@ -46,30 +112,100 @@ impl Day for Day11 {
     type Input2 = Input;
 
     fn get_name(&self) -> String { "Day 11".to_string() }
+    fn input_file(&self) -> String { "day11.txt".to_string() }
 
     fn input1(&self) -> &Self::Input1 { &self.input }
     fn input2(&self) -> &Self::Input2 { &self.input }
 
-    fn step1(&self, input: &Self::Input1) {
-        println!("Step 1: {}", input.count_paths("you", "out"));
+    fn step1(&self, input: &Self::Input1) -> Result<String, String> {
+        Ok(input.count_paths("you", "out").to_string())
     }
 
-    fn step2(&self, input: &Self::Input2) {
-        let s2d = input.count_paths("svr", "dac");
-        let s2f = input.count_paths("svr", "fft");
-        let d2f = input.count_paths("dac", "fft");
-        let f2d = input.count_paths("fft", "dac");
-        let d2o = input.count_paths("dac", "out");
-        let f2o = input.count_paths("fft", "out");
+    fn step2(&self, input: &Self::Input2) -> Result<String, String> {
+        let paths = |from, to| input.count_paths(from, to);
+        let s2d = paths("svr", "dac");
+        let s2f = paths("svr", "fft");
+        let d2f = paths("dac", "fft");
+        let f2d = paths("fft", "dac");
+        let d2o = paths("dac", "out");
+        let f2o = paths("fft", "out");
         let result = s2d * d2f * f2o + s2f * f2d * d2o;
-        println!("Step 2: {}", result);
+        Ok(result.to_string())
     }
 
     fn new() -> Self { Day11 { input: Input::new() } }
 
-    fn setup(&mut self) -> Result<(), String> {
-        let lines = read_lines("input/day11.txt")?;
+    fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+        generate(rng, size)
+    }
+
+    fn parse(&mut self, lines: Vec<String>) -> Result<(), String> {
         self.input = Input::from(lines)?;
         Ok(())
     }
+
+    fn queries(&self) -> &'static [&'static str] {
+        &["count_paths FROM TO", "outputs DEVICE"]
+    }
+
+    fn query(&self, name: &str, args: &[&str]) -> Result<String, String> {
+        match (name, args) {
+            ("count_paths", [from, to]) => Ok(self.input.count_paths(from, to).to_string()),
+            ("outputs", [device]) => self.input.network.get(*device)
+                .map(|outputs| Ok(outputs.join(" ")))
+                .unwrap_or(Err(format!("Unknown device: {}", device))),
+            _ => Err(unknown_query(self.queries(), name)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use crate::snapshot;
+
+    #[test]
+    fn parsed_example() {
+        let i = Input::from([
+            "aaa: you hhh", "you: bbb ccc", "bbb: ddd eee", "ccc: ddd eee fff", "ddd: ggg",
+            "eee: out", "fff: out", "ggg: out", "hhh: ccc fff iii", "iii: out",
+        ].map(ToOwned::to_owned).to_vec()).expect("Should be parsable");
+        // sorted, a HashMap has no stable order
+        let network = i.network.iter().collect::<BTreeMap<_, _>>();
+        snapshot::check("day11_example", &format!("{:#?}\n", network));
+    }
+
+    #[test]
+    fn generated() {
+        let i = Input::from(generate(&mut Rng::new(1), 30))
+            .expect("Should be parsable");
+        assert_eq!(i.network.len(), 29);
+        assert_ne!(i.count_paths("svr", "out"), Num::from(0));
+    }
+
+    #[test]
+    fn diamond_chain() {
+        // every level doubles the paths, 2^70 in all
+        let mut lines = (0..70).flat_map(|i| [
+            format!("n{}: a{} b{}", i, i, i),
+            format!("a{}: n{}", i, i + 1),
+            format!("b{}: n{}", i, i + 1),
+        ]).collect::<Vec<_>>();
+        lines.push("n70: out".to_string());
+        let i = Input::from(lines).expect("Should be parsable");
+        assert_eq!(i.count_paths("n0", "out"), Num::Small(1 << 70));
+        assert_eq!(i.count_paths("n69", "out"), Num::from(2));
+        assert_eq!(i.count_paths("out", "n0"), Num::from(0));
+    }
+
+    #[test]
+    fn looped_network() {
+        let lines = ["you: aaa", "aaa: bbb out", "bbb: aaa"]
+            .map(ToOwned::to_owned).to_vec();
+        assert_eq!(
+            Input::from(lines).err(),
+            Some("Network has a loop through aaa".to_string())
+        );
+    }
 }
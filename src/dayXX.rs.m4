@@ -1,4 +1,5 @@
 define(`DayXX', format(`Day%s', XX))dnl
+use crate::rng::Rng;
 use crate::types::Day;
 
 pub struct Input {}
@@ -16,21 +17,26 @@ impl Day for DayXX {
     type Input2 = Input;
 
     fn get_name(&self) -> String { "Day XX".to_string() }
+    fn input_file(&self) -> String { "dayXX.txt".to_string() }
 
     fn input1(&self) -> &Self::Input1 { &self.input }
     fn input2(&self) -> &Self::Input2 { &self.input }
 
-    fn step1(&self, _input: &Self::Input1) {
-        println!("Step 1: {}", "TODO");
+    fn step1(&self, _input: &Self::Input1) -> Result<String, String> {
+        Ok("TODO".to_string())
     }
 
-    fn step2(&self, _input: &Self::Input2) {
-        println!("Step 2: {}", "TODO");
+    fn step2(&self, _input: &Self::Input2) -> Result<String, String> {
+        Ok("TODO".to_string())
     }
 
     fn new() -> Self { DayXX { input: Input::new() } }
 
-    fn setup(&mut self) -> Result<(), String> {
+    fn generate(_rng: &mut Rng, _size: usize) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn parse(&mut self, _lines: Vec<String>) -> Result<(), String> {
         Ok(())
     }
 }
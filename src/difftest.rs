@@ -0,0 +1,120 @@
+use std::fmt::Debug;
+
+use crate::registry::Entry;
+use crate::rng::Rng;
+
+const SEEDS: u64 = 20;
+
+/// Feeds inputs of growing size from `generate` to both `fast` and `slow`
+/// solvers and panics with the smallest input on which they disagree.
+/// Solvers return `None` for inputs they can't parse, such inputs are
+/// skipped while shrinking.
+pub fn check<T, F, S>(
+    generate: fn(&mut Rng, usize) -> Vec<String>,
+    max_size: usize,
+    fast: F,
+    slow: S,
+)
+where
+    T: PartialEq + Debug,
+    F: Fn(&[String]) -> Option<T>,
+    S: Fn(&[String]) -> Option<T>,
+{
+    let disagree = |lines: &[String]| match (fast(lines), slow(lines)) {
+        (Some(f), Some(s)) => f != s,
+        _ => false,
+    };
+    for size in 1..=max_size {
+        for seed in 0..SEEDS {
+            let lines = generate(&mut Rng::new(seed), size);
+            if disagree(&lines) {
+                let smallest = shrink(lines, disagree);
+                panic!(
+                    "Solvers disagree (fast {:?}, slow {:?}) on:\n{}",
+                    fast(&smallest), slow(&smallest), smallest.join("\n")
+                );
+            }
+        }
+    }
+}
+
+/// Checks every named variant of the day's steps against the default solver.
+/// A variant failing where the default solver answers disagrees with it.
+pub fn check_variants(entry: &Entry, max_size: usize) {
+    for step in [1, 2] {
+        for name in (entry.variants)(step) {
+            check(
+                entry.generate, max_size,
+                |lines| (entry.step)(lines.to_vec(), step).ok().map(Ok),
+                |lines| Some(
+                    (entry.variant)(lines.to_vec(), step, name, None).and_then(|(a, _)| a)
+                ),
+            );
+        }
+    }
+}
+
+/// Drops lines one by one while the input stays failing.
+fn shrink<P>(mut lines: Vec<String>, failing: P) -> Vec<String>
+where P: Fn(&[String]) -> bool {
+    let mut i = 0;
+    while i < lines.len() {
+        let mut candidate = lines.clone();
+        candidate.remove(i);
+        if failing(&candidate) {
+            lines = candidate;
+        } else {
+            i += 1;
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbers(rng: &mut Rng, size: usize) -> Vec<String> {
+        (0..size).map(|_| rng.range(0..=20).to_string()).collect()
+    }
+
+    fn max(lines: &[String]) -> Option<usize> {
+        lines.iter().map(|l| l.parse::<usize>().ok()).max().flatten()
+    }
+
+    #[test]
+    fn agreeing() {
+        check(numbers, 5, max, |lines| {
+            let mut v = lines.iter()
+                .map(|l| l.parse::<usize>().ok())
+                .collect::<Option<Vec<_>>>()?;
+            v.sort();
+            v.last().copied()
+        });
+    }
+
+    #[test]
+    fn shrinking() {
+        let lines = ["1", "15", "3"].map(ToOwned::to_owned).to_vec();
+        let buggy = |ls: &[String]| max(ls).is_some_and(|m| m > 10);
+        assert_eq!(shrink(lines, buggy), vec!["15".to_string()]);
+    }
+
+    #[test]
+    fn variants() {
+        for entry in crate::registry::DAYS { check_variants(entry, 6) }
+    }
+
+    #[test]
+    #[should_panic(expected = "Solvers disagree")]
+    fn failing_variant() {
+        let entry = crate::registry::find(1).expect("Should exist");
+        check_variants(&Entry { variant: |_, _, _, _| Err("Broken".to_string()), ..*entry }, 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "Solvers disagree")]
+    fn disagreeing() {
+        check(numbers, 5, max, |lines| max(lines).map(|m| m.min(10)));
+    }
+}
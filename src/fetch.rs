@@ -0,0 +1,157 @@
+use std::fs;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::thread::sleep;
+use std::time::{Duration, SystemTime};
+
+pub const BASE_URL: &str = "https://adventofcode.com";
+const USER_AGENT: &str = "github.com/astynax/AdventOfCode2025";
+/// Touched after every download, so the rate limit holds across runs.
+const STAMP: &str = ".last-fetch";
+
+/// Downloads puzzle inputs into `dir`, at most one request per `interval`.
+pub struct Fetcher {
+    pub base_url: String,
+    pub session: Option<String>,
+    pub dir: PathBuf,
+    pub interval: Duration,
+}
+
+impl Fetcher {
+    /// Returns `false` if the input is already there.
+    pub fn fetch(&self, day: usize) -> Result<bool, String> {
+        let path = self.dir.join(format!("day{:02}.txt", day));
+        if path.exists() { return Ok(false) }
+        let session = self.session.as_ref()
+            .map(Result::Ok)
+            .unwrap_or(Err("Session token is not set".to_string()))?;
+        self.wait();
+        let url = format!("{}/2025/day/{}/input", self.base_url.trim_end_matches('/'), day);
+        let body = get(&url, session);
+        fs::create_dir_all(&self.dir)
+            .and_then(|_| fs::write(self.dir.join(STAMP), ""))
+            .map_err(|e| e.to_string())?;
+        let body = body?;
+        if body.trim().is_empty() { return Err(format!("Empty input at {}", url)) }
+        fs::write(&path, body).map_err(|e| e.to_string())?;
+        Ok(true)
+    }
+
+    fn wait(&self) {
+        let elapsed = fs::metadata(self.dir.join(STAMP))
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| SystemTime::now().duration_since(t).ok());
+        if let Some(left) = elapsed.and_then(|e| self.interval.checked_sub(e)) {
+            sleep(left);
+        }
+    }
+}
+
+fn get(url: &str, session: &str) -> Result<String, String> {
+    match url.strip_prefix("http://") {
+        Some(rest) => get_plain(rest, session),
+        None => get_curl(url, session),
+    }
+}
+
+/// A bare HTTP/1.0 request, enough for local stubs.
+fn get_plain(url: &str, session: &str) -> Result<String, String> {
+    let (host, path) = url.split_once('/').unwrap_or((url, ""));
+    let mut stream = TcpStream::connect(host).map_err(|e| format!("{}: {}", host, e))?;
+    write!(
+        stream,
+        "GET /{} HTTP/1.0\r\nHost: {}\r\nCookie: session={}\r\nUser-Agent: {}\r\n\r\n",
+        path, host, session, USER_AGENT
+    ).map_err(|e| e.to_string())?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response).map_err(|e| e.to_string())?;
+    let (head, body) = response.split_once("\r\n\r\n")
+        .map(Result::Ok)
+        .unwrap_or(Err("Malformed HTTP response".to_string()))?;
+    let status = head.lines().next().unwrap_or("");
+    match status.split_whitespace().nth(1) {
+        Some("200") => Ok(body.to_string()),
+        _ => Err(format!("{}: {}", status, body.lines().next().unwrap_or(""))),
+    }
+}
+
+/// HTTPS is left to curl, the cookie goes through stdin to stay out of `ps`.
+fn get_curl(url: &str, session: &str) -> Result<String, String> {
+    let mut child = Command::new("curl")
+        .args(["--silent", "--show-error", "--fail", "--user-agent", USER_AGENT])
+        .args(["--header", "@-", url])
+        .stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("curl: {}", e))?;
+    child.stdin.take()
+        .map(|mut stdin| writeln!(stdin, "Cookie: session={}", session))
+        .transpose()
+        .map_err(|e| e.to_string())?;
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+    String::from_utf8(output.stdout).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Answers a single request, returning what was asked for.
+    fn stub(status: &'static str, body: &'static str) -> (String, thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Should bind");
+        let base = format!("http://{}", listener.local_addr().expect("Should be bound"));
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().expect("Should connect");
+            let request = BufReader::new(&stream).lines()
+                .map_while(Result::ok)
+                .take_while(|l| !l.is_empty())
+                .collect::<Vec<_>>();
+            write!(stream, "HTTP/1.0 {}\r\n\r\n{}", status, body).expect("Should write");
+            request
+        });
+        (base, handle)
+    }
+
+    fn fetcher(base_url: String, name: &str) -> Fetcher {
+        let dir = std::env::temp_dir()
+            .join(format!("aoc-fetch-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        Fetcher {
+            base_url, dir,
+            session: Some("secret".to_string()),
+            interval: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn fetch_once() {
+        let (base, handle) = stub("200 OK", "1-2\n");
+        let f = fetcher(base, "once");
+        assert_eq!(f.fetch(2), Ok(true));
+        let request = handle.join().expect("Stub should finish");
+        assert_eq!(request[0], "GET /2025/day/2/input HTTP/1.0");
+        assert!(request.contains(&"Cookie: session=secret".to_string()));
+        assert_eq!(fs::read_to_string(f.dir.join("day02.txt")).ok(), Some("1-2\n".to_string()));
+        // the stub is gone, so only the cached file can satisfy this
+        assert_eq!(f.fetch(2), Ok(false));
+        let _ = fs::remove_dir_all(&f.dir);
+    }
+
+    #[test]
+    fn fetch_failure() {
+        let (base, handle) = stub("404 Not Found", "Not unlocked yet\n");
+        let f = fetcher(base, "failure");
+        assert_eq!(f.fetch(12), Err("HTTP/1.0 404 Not Found: Not unlocked yet".to_string()));
+        handle.join().expect("Stub should finish");
+        assert!(!f.dir.join("day12.txt").exists());
+        let _ = fs::remove_dir_all(&f.dir);
+    }
+}
@@ -0,0 +1,106 @@
+use std::time::Duration;
+
+use crate::cancel::{self, Token};
+use crate::registry::DAYS;
+use crate::rng::Rng;
+
+const CASES: u64 = 300;
+const DEADLINE: Duration = Duration::from_millis(50);
+const ALPHABET: &[char] = &[
+    'L', 'R', ',', '-', ':', '@', '.', '^', 'S', '*', '+', '#',
+    '[', ']', '(', ')', '{', '}', ' ', ' ', 'a', 'z', 'é',
+];
+
+/// Short lines of puzzle-like characters and small numbers.
+fn junk(rng: &mut Rng) -> Vec<String> {
+    (0..rng.range(0..=6)).map(|_| {
+        (0..rng.range(0..=8)).map(|_| {
+            if rng.chance(40) {
+                rng.range(0..=999).to_string()
+            } else {
+                ALPHABET[rng.range(0..=ALPHABET.len() - 1)].to_string()
+            }
+        }).collect()
+    }).collect()
+}
+
+/// Numbers at the edges of the integer types, to provoke overflows.
+const EDGES: &[u64] = &[
+    0, u64::MAX, u64::MAX - 1, u64::MAX / 2, u32::MAX as u64, u32::MAX as u64 + 1,
+];
+
+/// Breaks a valid input a bit. Digits are only replaced by digits
+/// and the rest by non-digits, so numbers keep their sizes.
+fn mutate(rng: &mut Rng, mut lines: Vec<String>) -> Vec<String> {
+    for _ in 0..rng.range(1..=3) {
+        if lines.is_empty() { break }
+        let i = rng.range(0..=lines.len() - 1);
+        match rng.range(0..=4) {
+            0 => { lines.remove(i); },
+            1 => lines.insert(i, lines[i].clone()),
+            2 => {
+                let j = rng.range(0..=lines.len() - 1);
+                lines.swap(i, j);
+            },
+            3 => {
+                let mut cut = rng.range(0..=lines[i].len());
+                while !lines[i].is_char_boundary(cut) { cut -= 1 }
+                lines[i].truncate(cut);
+            },
+            _ => {
+                let chars = lines[i].chars().collect::<Vec<_>>();
+                if chars.is_empty() { continue }
+                let j = rng.range(0..=chars.len() - 1);
+                let c = if chars[j].is_ascii_digit() {
+                    char::from(b'0' + rng.range(0..=9) as u8)
+                } else {
+                    ALPHABET[rng.range(0..=ALPHABET.len() - 1)]
+                };
+                lines[i] = chars.iter().enumerate()
+                    .map(|(k, x)| if k == j { c } else { *x })
+                    .collect();
+            },
+        }
+    }
+    lines
+}
+
+/// A valid input with all the numbers of a few lines replaced by `EDGES`.
+fn stretch(rng: &mut Rng, mut lines: Vec<String>) -> Vec<String> {
+    for _ in 0..rng.range(1..=3) {
+        if lines.is_empty() { break }
+        let i = rng.range(0..=lines.len() - 1);
+        let mut line = String::new();
+        let mut in_number = false;
+        for c in lines[i].chars() {
+            if !c.is_ascii_digit() {
+                line.push(c);
+            } else if !in_number {
+                line.push_str(&EDGES[rng.range(0..=EDGES.len() - 1)].to_string());
+            }
+            in_number = c.is_ascii_digit();
+        }
+        lines[i] = line;
+    }
+    lines
+}
+
+#[test]
+fn parsers_and_solvers_do_not_panic() {
+    for entry in DAYS {
+        for seed in 0..CASES {
+            let mut rng = Rng::new(seed);
+            let lines = if seed % 3 == 0 {
+                junk(&mut rng)
+            } else {
+                let size = rng.range(1..=5);
+                let valid = (entry.generate)(&mut rng, size);
+                if seed % 3 == 1 { mutate(&mut rng, valid) } else { stretch(&mut rng, valid) }
+            };
+            // a panic fails the test, any result is fine
+            let _ = cancel::scope(
+                &Token::new(Some(DEADLINE)), || (entry.solve)(lines)
+            );
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use crate::registry::{self, Entry};
+
+/// How well a day's parser takes some input.
+#[derive(Debug, PartialEq)]
+pub struct Fit {
+    pub day: usize,
+    /// Lines of the longest prefix the parser accepts.
+    pub parsed: usize,
+    pub total: usize,
+    /// Why the whole input doesn't parse.
+    pub error: Option<String>,
+}
+
+impl Fit {
+    /// Percentage of the lines that parse.
+    pub fn confidence(&self) -> usize {
+        (100 * self.parsed).checked_div(self.total).unwrap_or(0)
+    }
+}
+
+/// Tries the lines on every day, best fitting days first.
+pub fn identify(lines: &[String]) -> Vec<Fit> {
+    let mut fits = registry::DAYS.iter()
+        .map(|entry| fit(entry, lines))
+        .collect::<Vec<_>>();
+    fits.sort_by_key(|f| std::cmp::Reverse(f.parsed));
+    fits
+}
+
+/// Binary search for the longest parsable prefix, as if parsers that
+/// accept some lines also accepted the lines before them. Most do,
+/// the others only get credit for a complete input.
+fn fit(entry: &Entry, lines: &[String]) -> Fit {
+    let total = lines.len();
+    let Err(error) = (entry.parse)(lines.to_vec()) else {
+        return Fit { day: entry.day, parsed: total, total, error: None }
+    };
+    let (mut good, mut bad) = (0, total);
+    while bad - good > 1 {
+        let mid = (good + bad) / 2;
+        if (entry.parse)(lines[..mid].to_vec()).is_ok() { good = mid } else { bad = mid }
+    }
+    Fit { day: entry.day, parsed: good, total, error: Some(error) }
+}
+
+/// Other days that take the whole input, for a hint after `day` rejected it.
+pub fn other_days(day: &str, lines: &[String]) -> Vec<String> {
+    registry::DAYS.iter()
+        .filter(|entry| (entry.name)() != day)
+        .filter(|entry| !lines.is_empty() && (entry.parse)(lines.to_vec()).is_ok())
+        .map(|entry| (entry.name)())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(ToOwned::to_owned).collect()
+    }
+
+    #[test]
+    fn identify() {
+        let fits = super::identify(&lines("162,817,812\n57,618,57\n906,360,560"));
+        assert_eq!(fits[0], Fit { day: 8, parsed: 3, total: 3, error: None });
+        assert_eq!(fits[0].confidence(), 100);
+        assert!(fits[1..].iter().all(|f| f.error.is_some()));
+    }
+
+    #[test]
+    fn partial_fit() {
+        let fit = super::fit(registry::find(1).expect("Should exist"), &lines("L68\nR30\n7,1\nL5"));
+        assert_eq!((fit.parsed, fit.confidence()), (2, 50));
+    }
+
+    #[test]
+    fn other_days() {
+        assert_eq!(super::other_days("Day 09", &lines("1,2,3\n4,5,6")), vec!["Day 08"]);
+        assert!(super::other_days("Day 09", &[]).is_empty());
+    }
+}
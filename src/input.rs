@@ -1,14 +1,7 @@
 use std::fs::read_to_string;
+use std::path::Path;
 
-pub fn read_single_line(path: &str) -> Result<String, String> {
-    let text = read_to_string(path)
-        .map(|s| s.trim_end().to_string())
-        .map_err(|e| e.to_string())?;
-    if text.contains("\n") { return Err("Unexpected newline".to_string()) }
-    Ok(text)
-}
-
-pub fn read_lines(path: &str) -> Result<Vec<String>, String> {
+pub fn read_lines<P: AsRef<Path>>(path: P) -> Result<Vec<String>, String> {
     let text = read_to_string(path)
         .map_err(|e| e.to_string())?;
     Ok(text
@@ -0,0 +1,210 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// What the puzzle site said about an answer.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Feedback { TooHigh, TooLow, Wrong, Correct }
+
+impl FromStr for Feedback {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Feedback, String> {
+        match s {
+            "too-high" => Ok(Feedback::TooHigh),
+            "too-low" => Ok(Feedback::TooLow),
+            "wrong" => Ok(Feedback::Wrong),
+            "correct" => Ok(Feedback::Correct),
+            _ => Err(format!("Unknown feedback: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Feedback {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Feedback::TooHigh => "too-high",
+            Feedback::TooLow => "too-low",
+            Feedback::Wrong => "wrong",
+            Feedback::Correct => "correct",
+        })
+    }
+}
+
+/// An answer tried for a step of a day (by its slug, e.g. "day09").
+#[derive(Debug, PartialEq)]
+pub struct Attempt {
+    pub day: String,
+    pub step: usize,
+    pub answer: String,
+    pub feedback: Feedback,
+}
+
+impl Attempt {
+    fn from_line(line: &str) -> Result<Attempt, String> {
+        let [day, step, answer, feedback] = line.split_whitespace().collect::<Vec<_>>()[..]
+        else { return Err(format!("Bad ledger line: {}", line)) };
+        Ok(Attempt {
+            day: day.to_string(),
+            step: step.parse().map_err(|_| format!("Bad ledger line: {}", line))?,
+            answer: answer.to_string(),
+            feedback: feedback.parse()?,
+        })
+    }
+}
+
+impl fmt::Display for Attempt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} {} {}", self.day, self.step, self.answer, self.feedback)
+    }
+}
+
+/// Every answer we tried, one attempt per line of a text file.
+pub struct Ledger {
+    path: PathBuf,
+}
+
+impl Ledger {
+    pub fn new(path: impl AsRef<Path>) -> Ledger { Ledger { path: path.as_ref().to_path_buf() } }
+
+    /// The attempts of a day, or of all days, and warnings about the
+    /// lines that are skipped because they don't parse.
+    pub fn attempts(&self, day: Option<&str>) -> (Vec<Attempt>, Vec<String>) {
+        let Ok(text) = fs::read_to_string(&self.path) else { return (Vec::new(), Vec::new()) };
+        let (mut attempts, mut warnings) = (Vec::new(), Vec::new());
+        for (n, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() { continue }
+            if day.is_some_and(|d| line.split_whitespace().next() != Some(d)) { continue }
+            match Attempt::from_line(line) {
+                Ok(attempt) => attempts.push(attempt),
+                Err(e) => warnings.push(format!(
+                    "Skipping line {} of {}: {}", n + 1, self.path.display(), e
+                )),
+            }
+        }
+        (attempts, warnings)
+    }
+
+    pub fn record(&self, attempt: &Attempt) -> Result<(), String> {
+        if let Some(dir) = self.path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        }
+        fs::OpenOptions::new().create(true).append(true).open(&self.path)
+            .and_then(|mut f| writeln!(f, "{}", attempt))
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// How a fresh answer relates to the recorded attempts.
+#[derive(Debug, PartialEq)]
+pub enum Check {
+    Correct,
+    Unknown,
+    Wrong(String),
+}
+
+/// Compares the answer to the attempts of the same step.
+pub fn check(attempts: &[Attempt], step: usize, answer: &str) -> Check {
+    let attempts = attempts.iter().filter(|a| a.step == step).collect::<Vec<_>>();
+    if let Some(a) = attempts.iter().find(|a| a.answer == answer) {
+        return match a.feedback {
+            Feedback::Correct => Check::Correct,
+            feedback => Check::Wrong(format!("{} is already known to be {}", answer, feedback)),
+        }
+    }
+    if let Some(a) = attempts.iter().find(|a| a.feedback == Feedback::Correct) {
+        return Check::Wrong(format!("{} differs from the correct {}", answer, a.answer))
+    }
+    for a in attempts {
+        let bound = match (a.feedback, compare(answer, &a.answer)) {
+            (Feedback::TooHigh, Some(Ordering::Greater)) => "above",
+            (Feedback::TooLow, Some(Ordering::Less)) => "below",
+            _ => continue,
+        };
+        return Check::Wrong(format!(
+            "{} is {} {}, which is {}", answer, bound, a.answer, a.feedback
+        ))
+    }
+    Check::Unknown
+}
+
+/// Numeric order of non-negative decimal answers of any length.
+fn compare(a: &str, b: &str) -> Option<Ordering> {
+    let digits = |s: &str| {
+        let s = s.trim_start_matches('0');
+        s.bytes().all(|c| c.is_ascii_digit()).then_some(s.to_string())
+    };
+    let (a, b) = (digits(a)?, digits(b)?);
+    Some(a.len().cmp(&b.len()).then(a.cmp(&b)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attempts(lines: &[&str]) -> Vec<Attempt> {
+        lines.iter()
+            .map(|l| Attempt::from_line(l).expect("Should be parsable"))
+            .collect()
+    }
+
+    #[test]
+    fn check_bounds() {
+        let a = attempts(&["day09 2 500 too-high", "day09 2 100 too-low", "day09 1 7 wrong"]);
+        assert_eq!(check(&a, 2, "300"), Check::Unknown);
+        assert_eq!(check(&a, 1, "300"), Check::Unknown);
+        assert_eq!(check(&a, 1, "7"), Check::Wrong("7 is already known to be wrong".to_string()));
+        assert_eq!(
+            check(&a, 2, "1000"),
+            Check::Wrong("1000 is above 500, which is too-high".to_string())
+        );
+        assert_eq!(
+            check(&a, 2, "99"),
+            Check::Wrong("99 is below 100, which is too-low".to_string())
+        );
+    }
+
+    #[test]
+    fn check_correct() {
+        let a = attempts(&["day10 1 41 wrong", "day10 1 42 correct"]);
+        assert_eq!(check(&a, 1, "42"), Check::Correct);
+        assert_eq!(check(&a, 1, "43"), Check::Wrong("43 differs from the correct 42".to_string()));
+    }
+
+    #[test]
+    fn record() {
+        let dir = std::env::temp_dir().join(format!("aoc-ledger-{}", std::process::id()));
+        let path = dir.join("input/ledger.txt");
+        let ledger = Ledger::new(&path);
+        assert_eq!(ledger.attempts(None), (Vec::new(), Vec::new()));
+        let attempt = Attempt {
+            day: "day09".to_string(), step: 2, answer: "123".to_string(),
+            feedback: Feedback::TooLow,
+        };
+        ledger.record(&attempt).expect("Should be writable");
+        assert_eq!(ledger.attempts(Some("day09")), (vec![attempt], Vec::new()));
+        assert_eq!(ledger.attempts(Some("day10")), (Vec::new(), Vec::new()));
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn bad_lines() {
+        let path = std::env::temp_dir().join(format!("aoc-ledger-bad-{}", std::process::id()));
+        fs::write(&path, "day09 2 123 too-low
+day09 two 5 wrong
+day10 1 7 maybe
+")
+            .expect("Should be writable");
+        let (attempts, warnings) = Ledger::new(&path).attempts(Some("day09"));
+        assert_eq!(attempts.len(), 1);
+        assert_eq!(warnings, vec![format!(
+            "Skipping line 2 of {}: Bad ledger line: day09 two 5 wrong", path.display()
+        )]);
+        assert_eq!(Ledger::new(&path).attempts(None).1.len(), 2);
+        let _ = fs::remove_file(path);
+    }
+}
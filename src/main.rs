@@ -1,5 +1,33 @@
 mod types;
 mod input;
+mod cli;
+mod config;
+mod registry;
+mod rng;
+mod num;
+mod audit;
+mod cache;
+mod memory;
+mod certificate;
+mod ledger;
+mod fetch;
+mod identify;
+mod cancel;
+mod par;
+mod progress;
+mod watch;
+mod status;
+mod batch;
+mod serve;
+mod repl;
+mod report;
+mod bench;
+#[cfg(test)]
+mod difftest;
+#[cfg(test)]
+mod fuzz;
+#[cfg(test)]
+mod snapshot;
 mod day01;
 mod day02;
 mod day03;
@@ -12,25 +40,366 @@ mod day09;
 mod day10;
 mod day11;
 
-use types::Day;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use cache::Cache;
+use cli::Args;
+use config::Config;
+use ledger::{Attempt, Ledger};
+use registry::Entry;
+use types::{Options, Output};
+
+const CONFIG: &str = "aoc.toml";
+const LEDGER: &str = "ledger.txt";
+const BASELINES_DIR: &str = ".bench";
 
 fn main() {
-    let args: Vec<usize> = std::env::args().skip(1).map(
-        |s| s.parse::<usize>()
-            .unwrap_or_else(|err| {
-                eprintln!("{}", err);
+    let mut args = std::env::args().skip(1).peekable();
+    let result = match args.peek().map(String::as_str) {
+        Some("gen") => Args::parse(args.skip(1), &[]).and_then(|a| generate(&a)),
+        Some("cache") => Args::parse(args.skip(1), &[]).and_then(|a| cache(&a)),
+        Some("certify") => Args::parse(args.skip(1), &[]).and_then(|a| certify(&a)),
+        Some("bench") => Args::parse(args.skip(1), &[]).and_then(|a| bench(&a)),
+        Some("report") => Args::parse(args.skip(1), &[]).and_then(|a| report(&a)),
+        Some("repl") => Args::parse(args.skip(1), &[]).and_then(|a| repl(&a)),
+        Some("serve") => Args::parse(args.skip(1), &[]).and_then(|a| serve(&a)),
+        Some("batch") => Args::parse(args.skip(1), &[]).and_then(|a| batch(&a)),
+        Some("status") => Args::parse(args.skip(1), &[]).and_then(|a| status(&a)),
+        Some("fetch") => Args::parse(args.skip(1), &[]).and_then(|a| fetch(&a)),
+        Some("ledger") => Args::parse(args.skip(1), &[]).and_then(|a| ledger(&a)),
+        Some("identify") => Args::parse(args.skip(1), &[]).and_then(|a| identify(&a)),
+        Some("watch") => Args::parse(args.skip(1), &[]).and_then(|a| watch(&a)),
+        _ => Args::parse(args, &["--audit", "--no-cache", "--memory"]).and_then(|a| run(&a)),
+    };
+    if let Err(err) = result {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    }
+}
+
+/// The config file, `--config` or `aoc.toml`, overridden by the flags.
+/// Its thread count applies right away.
+fn settings(args: &Args) -> Result<Config, String> {
+    let path = args.get::<String>("--config")?.unwrap_or(CONFIG.to_string());
+    let config = Config::load(Path::new(&path))?.overridden(args)?;
+    if let Some(threads) = config.threads { par::set_threads(threads) }
+    Ok(config)
+}
+
+fn run(args: &Args) -> Result<(), String> {
+    let config = settings(args)?;
+    let audit = args.has("--audit");
+    if audit { audit::enable() };
+    // audited runs have to recompute everything
+    let cache = if audit || args.has("--no-cache") {
+        None
+    } else {
+        Some(Cache::new(&config.cache_dir))
+    };
+    let options = Options {
+        input_dir: config.input_dir.clone(),
+        timeout: config.timeout,
+        cache,
+        ledger: Ledger::new(config.input(LEDGER)),
+        memory: args.has("--memory"),
+        variant: args.get("--variant")?,
+    };
+    let days = config.days(args)?;
+    for day in &days { registry::find(*day)?; }
+    if let Some(variant) = &options.variant {
+        let known = days.iter()
+            .filter_map(|day| registry::find(*day).ok())
+            .any(|e| [1, 2].into_iter().any(|step| (e.variants)(step).contains(&variant.as_str())));
+        if !known { return Err(format!("No step has a variant {}", variant)) }
+    }
+    let jobs = args.get::<usize>("--jobs")?.unwrap_or(1);
+    if jobs == 0 { return Err("--jobs should be at least 1".to_string()) }
+    // the allocation counters are shared by all threads
+    if options.memory && jobs > 1 { return Err("--memory needs --jobs 1".to_string()) }
+    if options.memory { memory::enable() }
+    let entries = registry::DAYS.iter()
+        .filter(|e| days.contains(&e.day))
+        .collect::<Vec<_>>();
+    run_days(&entries, &options, jobs);
+    Ok(())
+}
+
+/// Runs the days on `jobs` threads, printing each day's report in day order
+/// as soon as every day before it is done. A single job prints as it goes.
+fn run_days(entries: &[&Entry], options: &Options, jobs: usize) {
+    if jobs == 1 {
+        for entry in entries {
+            if let Err(err) = (entry.run)(options, &mut Output::live()) {
+                println!("Error: {}", err);
                 std::process::exit(1);
-            })).collect();
-    let days = if args.is_empty() { (1..12).collect() } else { args };
-    if days.contains(&1) { day01::Day01::new().run(); }
-    if days.contains(&2) { day02::Day02::new().run(); }
-    if days.contains(&3) { day03::Day03::new().run(); }
-    if days.contains(&4) { day04::Day04::new().run(); }
-    if days.contains(&5) { day05::Day05::new().run(); }
-    if days.contains(&6) { day06::Day06::new().run(); }
-    if days.contains(&7) { day07::Day07::new().run(); }
-    if days.contains(&8) { day08::Day08::new().run(); }
-    if days.contains(&9) { day09::Day09::new().run(); }
-    if days.contains(&10) { day10::Day10::new().run(); }
-    if days.contains(&11) { day11::Day11::new().run(); }
+            }
+        }
+        return
+    }
+    let next = AtomicUsize::new(0);
+    let (send, receive) = mpsc::channel();
+    thread::scope(|s| {
+        for _ in 0..jobs.min(entries.len()) {
+            let (next, send) = (&next, send.clone());
+            s.spawn(move || loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(entry) = entries.get(i) else { break };
+                let mut out = Output::buffered();
+                let result = (entry.run)(options, &mut out);
+                if send.send((i, out.into_lines(), result)).is_err() { break }
+            });
+        }
+        drop(send);
+        let mut done = BTreeMap::new();
+        let mut printed = 0;
+        for (i, out, result) in receive {
+            done.insert(i, (out, result));
+            while let Some((out, result)) = done.remove(&printed) {
+                for line in out { println!("{}", line) }
+                if let Err(err) = result {
+                    println!("Error: {}", err);
+                    std::process::exit(1);
+                }
+                printed += 1;
+            }
+        }
+    });
+}
+
+fn generate(args: &Args) -> Result<(), String> {
+    let [day] = args.days()?[..] else {
+        return Err("Usage: gen DAY [--seed N] [--size N] [--out FILE]".to_string())
+    };
+    let mut rng = rng::Rng::new(args.get("--seed")?.unwrap_or(1));
+    let size = args.get("--size")?.unwrap_or(10);
+    let lines = (registry::find(day)?.generate)(&mut rng, size);
+    let text = lines.iter().map(|l| format!("{}\n", l)).collect::<String>();
+    match args.get::<String>("--out")? {
+        Some(path) => std::fs::write(path, text).map_err(|e| e.to_string()),
+        None => { print!("{}", text); Ok(()) },
+    }
+}
+
+/// Which days' parsers take a file, and how much of it.
+fn identify(args: &Args) -> Result<(), String> {
+    let [file] = &args.positional[..] else {
+        return Err("Usage: identify FILE".to_string())
+    };
+    for fit in identify::identify(&input::read_lines(file)?) {
+        let name = (registry::find(fit.day)?.name)();
+        let parsed = format!("{:>3}% ({}/{} lines)", fit.confidence(), fit.parsed, fit.total);
+        match fit.error {
+            None => println!("{}: {}", name, parsed),
+            Some(e) => println!("{}: {}, {}", name, parsed, e),
+        }
+    }
+    Ok(())
+}
+
+fn watch(args: &Args) -> Result<(), String> {
+    let [day] = args.days()?[..] else {
+        return Err("Usage: watch DAY [--timeout SECS]".to_string())
+    };
+    let config = settings(args)?;
+    watch::watch(registry::find(day)?, &config.input_dir, config.timeout)
+}
+
+/// Times the days, saving the timings as a named baseline
+/// or comparing them to one.
+fn bench(args: &Args) -> Result<(), String> {
+    let config = settings(args)?;
+    let runs = args.get("--runs")?.unwrap_or(5);
+    let baseline = |name: &str| std::path::Path::new(BASELINES_DIR).join(name);
+    let mut timings = bench::Timings::new();
+    for day in config.days(args)? {
+        let entry = registry::find(day)?;
+        bench::measure(entry, &config.input_dir, runs, config.timeout, &mut timings)?;
+    }
+    // loaded before saving, the two may be the same baseline
+    let compared = args.get::<String>("--compare")?
+        .map(|name| bench::load(&baseline(&name)).map(|timings| (name, timings)))
+        .transpose()?;
+    // saved before comparing, a slower run is still worth keeping
+    if let Some(name) = args.get::<String>("--save")? {
+        bench::save(&baseline(&name), &timings)?;
+    }
+    if let Some((name, before)) = compared {
+        let threshold = args.get("--threshold")?.unwrap_or(10.0);
+        let (lines, slower) = bench::compare(&before, &timings, threshold);
+        for line in lines { println!("{}", line) }
+        if slower > 0 {
+            return Err(format!("{} phases are slower than in {}", slower, name))
+        }
+    } else {
+        for ((day, phase), t) in &timings {
+            println!("{} {} {}", day, phase, status::short_duration(*t));
+        }
+    }
+    Ok(())
+}
+
+fn report(args: &Args) -> Result<(), String> {
+    if !args.positional.is_empty() {
+        return Err("Usage: report [--format md|html] [--out FILE] [--tests CARGO_TEST_OUTPUT]".to_string())
+    }
+    let config = settings(args)?;
+    let tests = match args.get::<String>("--tests")? {
+        Some(path) => Some(report::test_results(Path::new(&path))?),
+        None => None,
+    };
+    let rows = registry::DAYS.iter()
+        .map(|entry| report::row(entry, &config.input_dir, config.timeout, tests.as_ref()))
+        .collect::<Vec<_>>();
+    let text = match config.format.as_str() {
+        "md" => report::markdown(&rows),
+        "html" => report::html(&rows),
+        other => return Err(format!("Unknown format: {}", other)),
+    };
+    match args.get::<String>("--out")? {
+        Some(path) => std::fs::write(path, text).map_err(|e| e.to_string()),
+        None => { print!("{}", text); Ok(()) },
+    }
+}
+
+fn repl(args: &Args) -> Result<(), String> {
+    let [day] = args.days()?[..] else {
+        return Err("Usage: repl DAY [--input FILE]".to_string())
+    };
+    let entry = registry::find(day)?;
+    let config = settings(args)?;
+    let file = match args.get::<String>("--input")? {
+        Some(file) => PathBuf::from(file),
+        None => config.input(&(entry.input_file)()),
+    };
+    let lines = input::read_lines(&file)?;
+    (entry.repl)(lines, config.timeout, &mut std::io::stdin().lock(), &mut std::io::stdout())
+}
+
+fn serve(args: &Args) -> Result<(), String> {
+    if !args.positional.is_empty() {
+        return Err("Usage: serve [--port N] [--timeout SECS]".to_string())
+    }
+    serve::serve(args.get("--port")?.unwrap_or(2025), settings(args)?.timeout)
+}
+
+fn certify(args: &Args) -> Result<(), String> {
+    let [day] = args.days()?[..] else {
+        return Err("Usage: certify DAY".to_string())
+    };
+    let entry = registry::find(day)?;
+    let config = settings(args)?;
+    let lines = input::read_lines(config.input(&(entry.input_file)()))?;
+    for (i, certified) in (entry.certify)(lines.clone(), config.timeout)?.into_iter().enumerate() {
+        let step = i + 1;
+        let text = match certified {
+            Err(e) => format!("Error: {}", e),
+            Ok((answer, None)) => format!("{} (no certificate)", answer),
+            Ok((answer, Some(c))) => match certificate::check(day, step, &lines, &answer, &c) {
+                Ok(certificate::Proof::Certified) => format!("{} (certified)", answer),
+                Ok(certificate::Proof::FeasibleOnly) => format!("{} (feasible only)", answer),
+                Err(e) => format!("{} (rejected: {})", answer, e),
+            },
+        };
+        println!("Step {}: {}", step, text);
+    }
+    Ok(())
+}
+
+fn batch(args: &Args) -> Result<(), String> {
+    let [day, dir] = &args.positional[..] else {
+        return Err("Usage: batch DAY DIR [--timeout SECS]".to_string())
+    };
+    let day = day.parse::<usize>().map_err(|e| format!("{}: {}", day, e))?;
+    let rows = batch::run(registry::find(day)?, dir, settings(args)?.timeout)?;
+    for line in batch::render(&rows) { println!("{}", line) }
+    Ok(())
+}
+
+fn status(args: &Args) -> Result<(), String> {
+    if !args.positional.is_empty() { return Err("Usage: status".to_string()) }
+    let config = settings(args)?;
+    let cache = Cache::new(&config.cache_dir);
+    let ledger = Ledger::new(config.input(LEDGER));
+    for warning in ledger.attempts(None).1 { eprintln!("Warning: {}", warning) }
+    let days = registry::DAYS.iter()
+        .map(|entry| {
+            let (attempts, _) = ledger.attempts(Some(&types::slug(&(entry.name)())));
+            status::day_status(entry, &config.input_dir, &cache, &attempts)
+        })
+        .collect::<Vec<_>>();
+    for line in status::render(&days) { println!("{}", line) }
+    Ok(())
+}
+
+/// Downloads missing inputs, `AOC_SESSION` holds the session cookie.
+fn fetch(args: &Args) -> Result<(), String> {
+    let base_url = match args.get::<String>("--base-url")? {
+        Some(url) => url,
+        None => std::env::var("AOC_BASE_URL").unwrap_or(fetch::BASE_URL.to_string()),
+    };
+    let config = settings(args)?;
+    let fetcher = fetch::Fetcher {
+        base_url,
+        session: std::env::var("AOC_SESSION").ok(),
+        dir: config.input_dir.clone(),
+        interval: args.seconds("--interval")?.unwrap_or(Duration::from_secs(5)),
+    };
+    for day in config.days(args)? {
+        registry::find(day)?;
+        let fetched = fetcher.fetch(day)?;
+        println!("Day {:02}: {}", day, if fetched { "downloaded" } else { "cached" });
+    }
+    Ok(())
+}
+
+fn ledger(args: &Args) -> Result<(), String> {
+    let ledger = Ledger::new(settings(args)?.input(LEDGER));
+    match args.positional.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        ["list"] | [] => {
+            let (attempts, warnings) = ledger.attempts(None);
+            for warning in warnings { eprintln!("Warning: {}", warning) }
+            for attempt in attempts { println!("{}", attempt) }
+            Ok(())
+        },
+        ["add", day, step, answer, feedback] => {
+            let day = day.parse::<usize>().map_err(|e| format!("{}: {}", day, e))?;
+            let step = match step {
+                "1" => 1,
+                "2" => 2,
+                _ => return Err(format!("Unknown step: {}", step)),
+            };
+            ledger.record(&Attempt {
+                day: types::slug(&(registry::find(day)?.name)()),
+                step,
+                answer: answer.to_string(),
+                feedback: feedback.parse()?,
+            })
+        },
+        _ => Err("Usage: ledger [list | add DAY STEP ANSWER too-high|too-low|wrong|correct]".to_string()),
+    }
+}
+
+fn cache(args: &Args) -> Result<(), String> {
+    let cache = Cache::new(&settings(args)?.cache_dir);
+    match args.positional.first().map(String::as_str) {
+        Some("list") | None => {
+            let build = cache::build_fingerprint();
+            for (key, answer) in cache.entries()? {
+                println!(
+                    "{} step {} input {:016x}{}: {}",
+                    key.day, key.step, key.input,
+                    if key.build == build { "" } else { " (old build)" },
+                    answer
+                );
+            }
+        },
+        Some("clear") => println!("Removed {} entries", cache.clear()?),
+        Some(other) => return Err(format!("Usage: cache [list|clear], not {}", other)),
+    }
+    Ok(())
 }
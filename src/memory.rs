@@ -0,0 +1,145 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicIsize, AtomicUsize, Ordering};
+
+/// The system allocator, counting what goes through it once `enable`d.
+/// The counters are shared by all threads, so the numbers are exact with
+/// one job at a time.
+struct Counting;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static BYTES: AtomicUsize = AtomicUsize::new(0);
+// signed, as memory allocated before counting may be freed after
+static LIVE: AtomicIsize = AtomicIsize::new(0);
+static PEAK: AtomicIsize = AtomicIsize::new(0);
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+/// Starts counting, until then the allocator only passes the calls on.
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+impl Counting {
+    fn enabled() -> bool {
+        ENABLED.load(Ordering::Relaxed)
+    }
+
+    fn grow(size: usize) {
+        if !Counting::enabled() { return }
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_add(size, Ordering::Relaxed);
+        let live = LIVE.fetch_add(size as isize, Ordering::Relaxed) + size as isize;
+        PEAK.fetch_max(live, Ordering::Relaxed);
+    }
+
+    fn shrink(size: usize) {
+        if !Counting::enabled() { return }
+        LIVE.fetch_sub(size as isize, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() { Counting::grow(layout.size()) }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc_zeroed(layout) };
+        if !ptr.is_null() { Counting::grow(layout.size()) }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        Counting::shrink(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = unsafe { System.realloc(ptr, layout, new_size) };
+        // only the growth is newly allocated
+        if !new.is_null() {
+            match new_size.checked_sub(layout.size()) {
+                Some(growth) => Counting::grow(growth),
+                None => Counting::shrink(layout.size() - new_size),
+            }
+        }
+        new
+    }
+}
+
+/// What a piece of code allocated: how many times, how much in total,
+/// and the most it held at once on top of what was live before it.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Stats {
+    pub allocations: usize,
+    pub bytes: usize,
+    pub peak: usize,
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f, "{} allocations, {} allocated, {} peak",
+            self.allocations, human(self.bytes), human(self.peak)
+        )
+    }
+}
+
+/// Runs `f`, counting its allocations if counting is enabled. Nested
+/// measurements don't disturb the outer ones.
+pub fn measure<T, F>(f: F) -> (T, Stats)
+where F: FnOnce() -> T {
+    let (allocations, bytes) = (ALLOCATIONS.load(Ordering::Relaxed), BYTES.load(Ordering::Relaxed));
+    let live = LIVE.load(Ordering::Relaxed);
+    let outer_peak = PEAK.swap(live, Ordering::Relaxed);
+    let result = f();
+    let peak = PEAK.fetch_max(outer_peak, Ordering::Relaxed);
+    let stats = Stats {
+        allocations: ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        bytes: BYTES.load(Ordering::Relaxed) - bytes,
+        peak: (peak - live).max(0) as usize,
+    };
+    (result, stats)
+}
+
+fn human(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 { return format!("{} B", bytes) }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn measure() {
+        super::enable();
+        let (len, stats) = super::measure(|| {
+            let v = vec![0_u8; 1 << 20];
+            drop(vec![0_u8; 1 << 10]);
+            v.len()
+        });
+        assert_eq!(len, 1 << 20);
+        // other tests allocate concurrently, so only the lower bounds are exact
+        assert!(stats.allocations >= 2);
+        assert!(stats.bytes >= (1 << 20) + (1 << 10));
+        assert!(stats.peak > 0);
+    }
+
+    #[test]
+    fn human() {
+        assert_eq!(super::human(512), "512 B");
+        assert_eq!(super::human(3 * 1024 + 100), "3.1 KiB");
+        assert_eq!(super::human(5 << 30), "5.0 GiB");
+    }
+}
@@ -0,0 +1,139 @@
+use std::fmt;
+use std::iter::{Product, Sum};
+use std::ops::{Add, Mul};
+
+/// A non-negative answer: plain `u128` arithmetic until it overflows,
+/// arbitrary precision after that.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Num {
+    Small(u128),
+    /// Little-endian base 2^32 digits of a number above `u128::MAX`.
+    Big(Vec<u32>),
+}
+
+impl Num {
+    fn digits(&self) -> Vec<u32> {
+        match self {
+            Num::Small(n) => (0..4).map(|i| (n >> (32 * i)) as u32).collect(),
+            Num::Big(digits) => digits.clone(),
+        }
+    }
+
+    fn from_digits(mut digits: Vec<u32>) -> Num {
+        while digits.last() == Some(&0) { digits.pop(); }
+        if digits.len() > 4 { return Num::Big(digits) }
+        Num::Small(digits.iter().rev().fold(0, |acc, d| acc << 32 | *d as u128))
+    }
+}
+
+impl From<usize> for Num {
+    fn from(n: usize) -> Num { Num::Small(n as u128) }
+}
+
+impl Add for Num {
+    type Output = Num;
+
+    fn add(self, other: Num) -> Num {
+        if let (Num::Small(a), Num::Small(b)) = (&self, &other)
+            && let Some(sum) = a.checked_add(*b) {
+            return Num::Small(sum)
+        }
+        let (a, b) = (self.digits(), other.digits());
+        let mut out = Vec::with_capacity(a.len().max(b.len()) + 1);
+        let mut carry = 0_u64;
+        for i in 0..a.len().max(b.len()) {
+            let sum = *a.get(i).unwrap_or(&0) as u64
+                + *b.get(i).unwrap_or(&0) as u64
+                + carry;
+            out.push(sum as u32);
+            carry = sum >> 32;
+        }
+        out.push(carry as u32);
+        Num::from_digits(out)
+    }
+}
+
+impl Mul for Num {
+    type Output = Num;
+
+    fn mul(self, other: Num) -> Num {
+        if let (Num::Small(a), Num::Small(b)) = (&self, &other)
+            && let Some(product) = a.checked_mul(*b) {
+            return Num::Small(product)
+        }
+        let (a, b) = (self.digits(), other.digits());
+        let mut out = vec![0_u32; a.len() + b.len()];
+        for (i, x) in a.iter().enumerate() {
+            let mut carry = 0_u64;
+            for (j, y) in b.iter().enumerate() {
+                let t = out[i + j] as u64 + *x as u64 * *y as u64 + carry;
+                out[i + j] = t as u32;
+                carry = t >> 32;
+            }
+            out[i + b.len()] = carry as u32;
+        }
+        Num::from_digits(out)
+    }
+}
+
+impl Sum for Num {
+    fn sum<I: Iterator<Item = Num>>(iter: I) -> Num {
+        iter.fold(Num::Small(0), Add::add)
+    }
+}
+
+impl Product for Num {
+    fn product<I: Iterator<Item = Num>>(iter: I) -> Num {
+        iter.fold(Num::Small(1), Mul::mul)
+    }
+}
+
+impl fmt::Display for Num {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        const BASE: u64 = 1_000_000_000;
+        let mut digits = match self {
+            Num::Small(n) => return write!(f, "{}", n),
+            Num::Big(digits) => digits.clone(),
+        };
+        let mut chunks = Vec::new();  // base 10^9, little-endian
+        while !digits.is_empty() {
+            let mut rem = 0_u64;
+            for d in digits.iter_mut().rev() {
+                let current = rem << 32 | *d as u64;
+                *d = (current / BASE) as u32;
+                rem = current % BASE;
+            }
+            chunks.push(rem);
+            while digits.last() == Some(&0) { digits.pop(); }
+        }
+        let mut chunks = chunks.iter().rev();
+        write!(f, "{}", chunks.next().unwrap_or(&0))?;
+        chunks.try_for_each(|c| write!(f, "{:09}", c))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small() {
+        let n = Num::from(6_usize) * Num::from(7_usize) + Num::from(8_usize);
+        assert_eq!(n, Num::Small(50));
+        assert_eq!(n.to_string(), "50");
+    }
+
+    #[test]
+    fn overflow_to_big() {
+        let n = Num::Small(u128::MAX) + Num::from(1_usize);
+        assert_eq!(n.to_string(), "340282366920938463463374607431768211456");
+        let m = [1_usize << 63; 3].map(Num::from).into_iter().product::<Num>();
+        assert_eq!(m.to_string(), "784637716923335095479473677900958302012794430558004314112");
+    }
+
+    #[test]
+    fn back_to_small() {
+        let n = (Num::Small(u128::MAX) + Num::from(1_usize)) * Num::from(0_usize);
+        assert_eq!(n, Num::Small(0));
+    }
+}
@@ -0,0 +1,93 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+use crate::{audit, cancel, progress};
+
+/// Zero stands for all the available cores.
+static THREADS: AtomicUsize = AtomicUsize::new(0);
+
+/// How many threads `map` may use, 1 keeps everything on the calling thread.
+pub fn set_threads(n: usize) {
+    THREADS.store(n, Ordering::Relaxed);
+}
+
+fn threads() -> usize {
+    match THREADS.load(Ordering::Relaxed) {
+        0 => thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    }
+}
+
+/// Maps the items on several threads, keeping their order. The workers see
+/// the caller's cancellation token and audited step, and the progress is
+/// reported as items get done.
+pub fn map<T, R, F>(items: &[T], f: F) -> Vec<R>
+where T: Sync, R: Send, F: Fn(&T) -> R + Sync {
+    map_with(threads(), items, f)
+}
+
+fn map_with<T, R, F>(threads: usize, items: &[T], f: F) -> Vec<R>
+where T: Sync, R: Send, F: Fn(&T) -> R + Sync {
+    let threads = threads.min(items.len());
+    if threads <= 1 {
+        return items.iter().enumerate().map(|(i, item)| {
+            progress::items(i, items.len());
+            f(item)
+        }).collect()
+    }
+    let token = cancel::current();
+    let step = audit::current();
+    let next = AtomicUsize::new(0);
+    let (send, receive) = mpsc::channel();
+    let mut results = items.iter().map(|_| None).collect::<Vec<_>>();
+    thread::scope(|s| {
+        for _ in 0..threads {
+            let (next, send, f, token, step) = (&next, send.clone(), &f, &token, &step);
+            s.spawn(move || {
+                let work = || loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(item) = items.get(i) else { break };
+                    if send.send((i, f(item))).is_err() { break }
+                };
+                audit::within(step, || match token {
+                    Some(token) => cancel::scope(token, work),
+                    None => work(),
+                })
+            });
+        }
+        drop(send);
+        for (done, (i, result)) in receive.into_iter().enumerate() {
+            progress::items(done, items.len());
+            results[i] = Some(result);
+        }
+    });
+    results.into_iter()
+        .map(|r| r.expect("Every item should be mapped"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_order() {
+        let items = (0..100).collect::<Vec<usize>>();
+        let expected = items.iter().map(|i| i * i).collect::<Vec<_>>();
+        for threads in [1, 4] {
+            assert_eq!(map_with(threads, &items, |i| i * i), expected);
+        }
+        assert_eq!(map_with(4, &[] as &[usize], |i| *i), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn workers_see_cancellation() {
+        let token = cancel::Token::new(None);
+        token.cancel();
+        let results = cancel::scope(&token, || {
+            map_with(4, &[1, 2, 3], |_| cancel::check(|| "worker".to_string()))
+        });
+        assert_eq!(results, vec![Err("Cancelled: worker".to_string()); 3]);
+    }
+}
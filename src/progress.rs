@@ -0,0 +1,82 @@
+use std::cell::RefCell;
+use std::io::{IsTerminal, Write};
+use std::time::{Duration, Instant};
+
+const REFRESH: Duration = Duration::from_millis(100);
+
+struct Reporter {
+    label: String,
+    last: Option<Instant>,
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<Reporter>> = const { RefCell::new(None) };
+}
+
+/// Runs `f` with a live progress line labeled by `label` on stderr.
+/// Nothing is drawn when stderr is not a terminal.
+pub fn track<T, F>(label: &str, f: F) -> T
+where F: FnOnce() -> T {
+    if std::io::stderr().is_terminal() {
+        CURRENT.with_borrow_mut(|r| *r = Some(Reporter {
+            label: label.to_string(), last: None
+        }));
+    }
+    let result = f();
+    CURRENT.with_borrow_mut(|r| {
+        if let Some(Reporter { last: Some(_), .. }) = r.take() {
+            eprint!("\r\x1b[K");
+        }
+    });
+    result
+}
+
+/// Reports that `done` items out of `total` are processed.
+pub fn items(done: usize, total: usize) {
+    draw(|| render_items(done, total));
+}
+
+/// Reports the size of the current search frontier and its depth.
+pub fn frontier(size: usize, depth: usize) {
+    draw(|| render_frontier(size, depth));
+}
+
+fn draw<F>(text: F)
+where F: FnOnce() -> String {
+    CURRENT.with_borrow_mut(|r| {
+        let Some(reporter) = r else { return };
+        let now = Instant::now();
+        if reporter.last.is_some_and(|t| now - t < REFRESH) { return }
+        reporter.last = Some(now);
+        let mut err = std::io::stderr();
+        let _ = write!(err, "\r\x1b[K{}: {}", reporter.label, text());
+        let _ = err.flush();
+    });
+}
+
+fn render_items(done: usize, total: usize) -> String {
+    let percent = (done * 100).checked_div(total).unwrap_or(100);
+    format!("{}/{} ({}%)", done, total, percent)
+}
+
+fn render_frontier(size: usize, depth: usize) -> String {
+    format!("frontier {} at depth {}", size, depth)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render() {
+        assert_eq!(render_items(5, 20), "5/20 (25%)");
+        assert_eq!(render_items(0, 0), "0/0 (100%)");
+        assert_eq!(render_frontier(42, 3), "frontier 42 at depth 3");
+    }
+
+    #[test]
+    fn silent_without_tracking() {
+        items(1, 2);
+        assert_eq!(track("test", || 42), 42);
+    }
+}
@@ -0,0 +1,177 @@
+use std::io::{BufRead, Write};
+use std::time::{Duration, Instant};
+
+use crate::cancel::{self, Token};
+use crate::certificate::Certificate;
+use crate::repl;
+use crate::rng::Rng;
+use crate::types::{self, Day, Options, Output};
+use crate::{day01, day02, day03, day04, day05, day06};
+use crate::{day07, day08, day09, day10, day11};
+
+/// Answers (or errors) of both steps.
+pub type Answers = [Result<String, String>; 2];
+
+/// An answer with its certificate, if the day has any.
+pub type Certified = Result<(String, Option<Certificate>), String>;
+
+/// An answer (or error) with the time it took.
+pub type Timed = (Result<String, String>, Duration);
+
+/// Answers of both steps with the time each phase took.
+pub struct Profile {
+    pub parse: Duration,
+    pub steps: [Timed; 2],
+    pub svg: Option<String>,
+}
+
+/// An interactive session over the parsed lines, see `repl::run`.
+pub type Repl = fn(
+    Vec<String>, Option<Duration>, &mut dyn BufRead, &mut dyn Write
+) -> Result<(), String>;
+
+/// Certifies both steps, each within the timeout.
+pub type Certify = fn(Vec<String>, Option<Duration>) -> Result<[Certified; 2], String>;
+
+/// Solves a step with a named variant within the timeout, timing it.
+pub type Variant = fn(Vec<String>, usize, &str, Option<Duration>) -> Result<Timed, String>;
+
+/// Everything the runner knows about a single day.
+pub struct Entry {
+    pub day: usize,
+    pub name: fn() -> String,
+    pub input_file: fn() -> String,
+    pub run: fn(&Options, &mut Output) -> Result<(), String>,
+    pub generate: fn(&mut Rng, usize) -> Vec<String>,
+    pub parse: fn(Vec<String>) -> Result<(), String>,
+    pub solve: fn(Vec<String>) -> Result<Answers, String>,
+    pub step: fn(Vec<String>, usize) -> Result<String, String>,
+    pub certify: Certify,
+    pub repl: Repl,
+    /// Times the phases, each step within the timeout.
+    pub profile: fn(Vec<String>, Option<Duration>) -> Result<Profile, String>,
+    pub variants: fn(usize) -> &'static [&'static str],
+    pub variant: Variant,
+}
+
+const fn entry<T: Day>(day: usize) -> Entry {
+    Entry {
+        day,
+        name: name::<T>,
+        input_file: input_file::<T>,
+        run: run::<T>,
+        generate: T::generate,
+        parse: parse::<T>,
+        solve: solve::<T>,
+        step: step::<T>,
+        certify: certify::<T>,
+        repl: repl::run::<T>,
+        profile: profile::<T>,
+        variants: variants::<T>,
+        variant: variant::<T>,
+    }
+}
+
+pub const DAYS: &[Entry] = &[
+    entry::<day01::Day01>(1),
+    entry::<day02::Day02>(2),
+    entry::<day03::Day03>(3),
+    entry::<day04::Day04>(4),
+    entry::<day05::Day05>(5),
+    entry::<day06::Day06>(6),
+    entry::<day07::Day07>(7),
+    entry::<day08::Day08>(8),
+    entry::<day09::Day09>(9),
+    entry::<day10::Day10>(10),
+    entry::<day11::Day11>(11),
+];
+
+pub fn find(day: usize) -> Result<&'static Entry, String> {
+    DAYS.iter().find(|e| e.day == day)
+        .map(Result::Ok)
+        .unwrap_or(Err(format!("Unknown day: {}", day)))
+}
+
+fn name<T: Day>() -> String {
+    T::new().get_name()
+}
+
+fn input_file<T: Day>() -> String {
+    T::new().input_file()
+}
+
+fn run<T: Day>(options: &Options, out: &mut Output) -> Result<(), String> {
+    T::new().run(options, out)
+}
+
+/// Just parses the lines.
+fn parse<T: Day>(lines: Vec<String>) -> Result<(), String> {
+    T::new().parse(lines)
+}
+
+/// Parses the lines and solves both steps.
+fn solve<T: Day>(lines: Vec<String>) -> Result<Answers, String> {
+    let mut day = T::new();
+    day.parse(lines)?;
+    Ok([1, 2].map(|step| types::solve(&day, step, None)))
+}
+
+/// Parses the lines and solves just the given step.
+fn step<T: Day>(lines: Vec<String>, step: usize) -> Result<String, String> {
+    let mut day = T::new();
+    day.parse(lines)?;
+    types::solve(&day, step, None)
+}
+
+/// Parses the lines and solves both steps, certifying the answers.
+fn certify<T: Day>(
+    lines: Vec<String>, timeout: Option<Duration>
+) -> Result<[Certified; 2], String> {
+    let mut day = T::new();
+    day.parse(lines)?;
+    Ok([1, 2].map(|step| cancel::scope(&Token::new(timeout), || day.certified(step))))
+}
+
+/// Parses the lines and solves both steps, timing every phase.
+fn profile<T: Day>(lines: Vec<String>, timeout: Option<Duration>) -> Result<Profile, String> {
+    let mut day = T::new();
+    let started = Instant::now();
+    day.parse(lines)?;
+    let parse = started.elapsed();
+    let steps = [1, 2].map(|step| timed(timeout, || types::solve(&day, step, None)));
+    Ok(Profile { parse, steps, svg: day.svg() })
+}
+
+fn variants<T: Day>(step: usize) -> &'static [&'static str] {
+    T::new().variants(step)
+}
+
+/// Parses the lines and solves the step with a named variant, timing it.
+fn variant<T: Day>(
+    lines: Vec<String>, step: usize, name: &str, timeout: Option<Duration>
+) -> Result<Timed, String> {
+    let mut day = T::new();
+    day.parse(lines)?;
+    Ok(timed(timeout, || types::solve(&day, step, Some(name))))
+}
+
+/// Runs a solver with a token of its own, timing it.
+fn timed<F>(timeout: Option<Duration>, f: F) -> Timed
+where F: FnOnce() -> Result<String, String> {
+    let started = Instant::now();
+    let answer = cancel::scope(&Token::new(timeout), f);
+    (answer, started.elapsed())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_step() {
+        let entry = find(1).expect("Should exist");
+        let lines = vec!["L68".to_string()];
+        assert_eq!((entry.step)(lines.clone(), 2), Ok("1".to_string()));
+        assert_eq!((entry.step)(lines, 3), Err("Unknown step: 3".to_string()));
+    }
+}
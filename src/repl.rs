@@ -0,0 +1,71 @@
+use std::io::{BufRead, Write};
+use std::time::Duration;
+
+use crate::cancel::{self, Token};
+use crate::types::Day;
+
+/// Parses the lines once, then answers commands read from `input`
+/// until it ends or a `quit`, each within the timeout.
+pub fn run<T: Day>(
+    lines: Vec<String>, timeout: Option<Duration>, input: &mut dyn BufRead, out: &mut dyn Write
+) -> Result<(), String> {
+    let mut day = T::new();
+    day.parse(lines)?;
+    let io = |e: std::io::Error| e.to_string();
+    writeln!(out, "{} loaded, type \"help\" for commands", day.get_name()).map_err(io)?;
+    loop {
+        write!(out, "> ").and_then(|_| out.flush()).map_err(io)?;
+        let mut line = String::new();
+        if input.read_line(&mut line).map_err(io)? == 0 { break }
+        let words = line.split_whitespace().collect::<Vec<_>>();
+        let Some((command, args)) = words.split_first() else { continue };
+        if ["quit", "exit"].contains(command) { break }
+        let result = cancel::scope(&Token::new(timeout), || match *command {
+            "help" if day.queries().is_empty() => Ok(format!(
+                "step1\nstep2\nquit\n({} has no queries)", day.get_name()
+            )),
+            "help" => Ok(["step1", "step2", "quit"].iter()
+                .chain(day.queries())
+                .copied()
+                .collect::<Vec<_>>()
+                .join("\n")),
+            "step1" => day.step1(day.input1()),
+            "step2" => day.step2(day.input2()),
+            name => day.query(name, args),
+        });
+        match result {
+            Ok(text) => writeln!(out, "{}", text),
+            Err(e) => writeln!(out, "Error: {}", e),
+        }.map_err(io)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day01::Day01;
+    use crate::day11::Day11;
+
+    #[test]
+    fn session() {
+        let lines = ["you: aaa out", "aaa: out"].map(ToOwned::to_owned).to_vec();
+        let mut input = "count_paths you out\n\noutputs aaa\nbogus\nquit\nstep1\n".as_bytes();
+        let mut out = Vec::new();
+        run::<Day11>(lines, None, &mut input, &mut out).expect("Should run");
+        assert_eq!(
+            String::from_utf8(out).expect("Should be UTF-8"),
+            "Day 11 loaded, type \"help\" for commands\n\
+             > 2\n> > out\n> Error: Unknown query: bogus\n> "
+        );
+    }
+
+    #[test]
+    fn help_without_queries() {
+        let mut input = "help\n".as_bytes();
+        let mut out = Vec::new();
+        run::<Day01>(vec!["L68".to_string()], None, &mut input, &mut out).expect("Should run");
+        assert!(String::from_utf8(out).expect("Should be UTF-8")
+            .contains("step1\nstep2\nquit\n(Day 01 has no queries)\n"));
+    }
+}
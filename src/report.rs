@@ -0,0 +1,232 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::input::read_lines;
+use crate::registry::{Entry, Profile};
+use crate::status::short_duration;
+
+/// Everything the report says about a day.
+pub struct Row {
+    pub name: String,
+    /// Lines and bytes of the input.
+    pub input: Result<(usize, usize), String>,
+    pub profile: Result<Profile, String>,
+    /// Passed and failed unit tests of the day's module, or why they aren't known.
+    pub tests: Result<(usize, usize), String>,
+}
+
+/// The test counts come from `test_results`, if there are any.
+pub fn row(
+    entry: &Entry, input_dir: &Path, timeout: Option<Duration>,
+    tests: Option<&HashMap<String, (usize, usize)>>
+) -> Row {
+    let name = (entry.name)();
+    let lines = read_lines(input_dir.join((entry.input_file)()));
+    let input = lines.as_ref()
+        .map(|ls| (ls.len(), ls.iter().map(|l| l.len() + 1).sum()))
+        .map_err(Clone::clone);
+    let profile = lines.and_then(|lines| (entry.profile)(lines, timeout));
+    let module = format!("day{:02}", entry.day);
+    let tests = match tests {
+        Some(tests) => tests.get(&module).copied()
+            .map(Result::Ok)
+            .unwrap_or(Err("none".to_string())),
+        None => Err("not run".to_string()),
+    };
+    Row { name, input, profile, tests }
+}
+
+/// Passed and failed unit tests per module, from the saved output
+/// of `cargo test`.
+pub fn test_results(path: &Path) -> Result<HashMap<String, (usize, usize)>, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let results = parse_test_output(&text);
+    if results.is_empty() {
+        return Err(format!("{}: no test results, expected the output of cargo test", path.display()))
+    }
+    Ok(results)
+}
+
+fn parse_test_output(text: &str) -> HashMap<String, (usize, usize)> {
+    let mut results: HashMap<String, (usize, usize)> = HashMap::new();
+    for line in text.lines() {
+        let Some((name, outcome)) = line.strip_prefix("test ")
+            .and_then(|l| l.split_once(" ... ")) else { continue };
+        let module = name.split("::").next().unwrap_or(name).to_string();
+        let counts = results.entry(module).or_default();
+        match outcome {
+            "ok" => counts.0 += 1,
+            "FAILED" => counts.1 += 1,
+            _ => {},
+        }
+    }
+    results
+}
+
+/// Table cells: day, input, parse time, and answer with time for each step.
+fn cells(row: &Row) -> Vec<String> {
+    let input = match &row.input {
+        Ok((lines, bytes)) => format!("{} lines, {} bytes", lines, bytes),
+        Err(e) => e.clone(),
+    };
+    let mut cells = vec![row.name.clone(), input];
+    match &row.profile {
+        Ok(p) => {
+            cells.push(short_duration(p.parse));
+            for (answer, elapsed) in &p.steps {
+                cells.push(answer.clone().unwrap_or_else(|e| format!("Error: {}", e)));
+                cells.push(short_duration(*elapsed));
+            }
+        },
+        Err(e) => {
+            cells.push(format!("Error: {}", e));
+            cells.extend(["", "", "", ""].map(String::from));
+        },
+    }
+    cells.push(match &row.tests {
+        Ok((passed, 0)) => format!("{} passed", passed),
+        Ok((passed, failed)) => format!("{} passed, {} failed", passed, failed),
+        Err(e) => e.clone(),
+    });
+    cells
+}
+
+const HEADER: [&str; 8] = ["Day", "Input", "Parse", "Step 1", "Time", "Step 2", "Time", "Tests"];
+
+pub fn markdown(rows: &[Row]) -> String {
+    let line = |cells: &[String]| format!("| {} |\n", cells.join(" | "));
+    let mut out = String::from("# Advent of Code 2025\n\n");
+    out += &line(&HEADER.map(String::from));
+    out += &line(&HEADER.map(|_| "---".to_string()));
+    for row in rows {
+        out += &line(&cells(row).iter().map(|c| c.replace('|', "\\|")).collect::<Vec<_>>());
+    }
+    for row in rows {
+        if let Ok(Profile { svg: Some(svg), .. }) = &row.profile {
+            out += &format!(
+                "\n## {}\n\n![{}](data:image/svg+xml;base64,{})\n", row.name, row.name, base64(svg)
+            );
+        }
+    }
+    out
+}
+
+pub fn html(rows: &[Row]) -> String {
+    let line = |tag: &str, cells: &[String]| format!(
+        "<tr>{}</tr>\n",
+        cells.iter().map(|c| format!("<{0}>{1}</{0}>", tag, escape(c))).collect::<String>()
+    );
+    let mut out = String::from(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Advent of Code 2025</title>\n<style>\n\
+         body { font-family: sans-serif; }\n\
+         table { border-collapse: collapse; }\n\
+         th, td { border: 1px solid #ccc; padding: 0.2em 0.6em; }\n\
+         </style>\n</head>\n<body>\n<h1>Advent of Code 2025</h1>\n<table>\n"
+    );
+    out += &line("th", &HEADER.map(String::from));
+    for row in rows { out += &line("td", &cells(row)) }
+    out += "</table>\n";
+    for row in rows {
+        if let Ok(Profile { svg: Some(svg), .. }) = &row.profile {
+            out += &format!("<h2>{}</h2>\n{}\n", escape(&row.name), svg);
+        }
+    }
+    out + "</body>\n</html>\n"
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+fn base64(s: &str) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::new();
+    for chunk in s.as_bytes().chunks(3) {
+        let n = chunk.iter().enumerate().fold(0_u32, |n, (i, b)| n | (*b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            out.push(if i <= chunk.len() {
+                ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char
+            } else {
+                '='
+            });
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows() -> Vec<Row> {
+        vec![
+            Row {
+                name: "Day 01".to_string(),
+                input: Ok((3, 12)),
+                profile: Ok(Profile {
+                    parse: Duration::from_millis(2),
+                    steps: [
+                        (Ok("1".to_string()), Duration::from_millis(3)),
+                        (Err("a < b".to_string()), Duration::from_millis(4)),
+                    ],
+                    svg: Some("<svg/>".to_string()),
+                }),
+                tests: Ok((2, 1)),
+            },
+            Row {
+                name: "Day 02".to_string(),
+                input: Err("No such file".to_string()),
+                profile: Err("No such file".to_string()),
+                tests: Err("none".to_string()),
+            },
+        ]
+    }
+
+    #[test]
+    fn markdown() {
+        let md = super::markdown(&rows());
+        assert!(md.contains(
+            "| Day 01 | 3 lines, 12 bytes | 2ms | 1 | 3ms | Error: a < b | 4ms | 2 passed, 1 failed |"
+        ));
+        assert!(md.contains("| Day 02 | No such file | Error: No such file |  |  |  |  | none |"));
+        assert!(md.contains("![Day 01](data:image/svg+xml;base64,PHN2Zy8+)"));
+    }
+
+    #[test]
+    fn html() {
+        let html = super::html(&rows());
+        assert!(html.contains("<td>Error: a &lt; b</td>"));
+        assert!(html.contains("<h2>Day 01</h2>\n<svg/>"));
+    }
+
+    #[test]
+    fn parse_test_output() {
+        let results = super::parse_test_output(
+            "running 3 tests\ntest day01::tests::a ... ok\ntest day01::tests::b ... FAILED\n\
+             test num::tests::c ... ok\ntest result: FAILED. 2 passed; 1 failed"
+        );
+        assert_eq!(results.get("day01"), Some(&(1, 1)));
+        assert_eq!(results.get("num"), Some(&(1, 0)));
+    }
+
+    #[test]
+    fn test_results() {
+        let path = std::env::temp_dir().join(format!("aoc-tests-{}", std::process::id()));
+        assert!(super::test_results(&path).is_err());
+        fs::write(&path, "error: could not compile\n").expect("Should be writable");
+        assert!(super::test_results(&path).is_err());
+        fs::write(&path, "test day01::tests::a ... ok\n").expect("Should be writable");
+        assert_eq!(super::test_results(&path).map(|r| r.get("day01").copied()), Ok(Some((1, 0))));
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn base64() {
+        assert_eq!(super::base64("Ma"), "TWE=");
+        assert_eq!(super::base64("Man"), "TWFu");
+        assert_eq!(super::base64("M"), "TQ==");
+    }
+}
@@ -0,0 +1,50 @@
+use std::ops::RangeInclusive;
+
+/// A small seeded SplitMix64 generator, good enough for test inputs.
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng { Rng { state: seed } }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    pub fn range(&mut self, range: RangeInclusive<usize>) -> usize {
+        let (from, to) = range.into_inner();
+        let width = (to - from) as u64 + 1;
+        from + (self.next_u64() % width) as usize
+    }
+
+    pub fn chance(&mut self, percent: usize) -> bool {
+        self.range(1..=100) <= percent
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeded() {
+        let a = (0..10).map({ let mut r = Rng::new(42); move |_| r.next_u64() });
+        let b = (0..10).map({ let mut r = Rng::new(42); move |_| r.next_u64() });
+        assert!(a.eq(b));
+    }
+
+    #[test]
+    fn range_bounds() {
+        let mut r = Rng::new(1);
+        for _ in 0..1000 {
+            let x = r.range(3..=5);
+            assert!((3..=5).contains(&x));
+        }
+        assert_eq!(r.range(7..=7), 7);
+    }
+}
@@ -0,0 +1,246 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::cancel::{self, Token};
+use crate::registry;
+
+/// Solves stop after this long unless told otherwise.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+/// The biggest input accepted.
+const MAX_BODY: usize = 1 << 20;
+/// The most bytes of request line and headers read.
+const MAX_HEAD: u64 = 8 << 10;
+/// Connections handled at once, more are turned away.
+const MAX_CONNECTIONS: usize = 32;
+/// How long a client may take to send its request.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Answers `POST /day/{n}/step/{k}` requests with the input as the body,
+/// every connection on its own thread.
+pub fn serve(port: u16, timeout: Option<Duration>) -> Result<(), String> {
+    let timeout = timeout.or(Some(DEFAULT_TIMEOUT));
+    let listener = TcpListener::bind(("127.0.0.1", port)).map_err(|e| e.to_string())?;
+    println!("Listening on http://{}", listener.local_addr().map_err(|e| e.to_string())?);
+    let active = Arc::new(AtomicUsize::new(0));
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else { continue };
+        if active.fetch_add(1, Ordering::Relaxed) >= MAX_CONNECTIONS {
+            active.fetch_sub(1, Ordering::Relaxed);
+            let _ = reply(&stream, 503, &error("Too many connections"));
+            continue;
+        }
+        let slot = Slot(active.clone());
+        thread::spawn(move || {
+            // freed even when a solver panics
+            let _slot = slot;
+            if let Err(e) = handle(stream, timeout) { eprintln!("{}", e) }
+        });
+    }
+    Ok(())
+}
+
+/// A taken connection slot, given back on drop.
+struct Slot(Arc<AtomicUsize>);
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+fn handle(stream: TcpStream, timeout: Option<Duration>) -> Result<(), String> {
+    stream.set_read_timeout(Some(READ_TIMEOUT)).map_err(|e| e.to_string())?;
+    let mut reader = BufReader::new(&stream);
+    let Some((request_line, length)) = read_head(&mut reader)? else {
+        return reply(&stream, 431, &error(&format!("Headers over {} bytes", MAX_HEAD)))
+    };
+    if length > MAX_BODY {
+        return reply(&stream, 413, &error(&format!("Input over {} bytes", MAX_BODY)))
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).map_err(|e| e.to_string())?;
+    let body = String::from_utf8_lossy(&body);
+    let (status, json) = match request_line.split_whitespace().collect::<Vec<_>>()[..] {
+        [method, path, _] => respond(method, path, &body, timeout),
+        _ => (400, error("Bad request")),
+    };
+    reply(&stream, status, &json)
+}
+
+/// The request line and the body's length, None when the headers
+/// don't end within `MAX_HEAD` bytes.
+fn read_head(reader: &mut impl BufRead) -> Result<Option<(String, usize)>, String> {
+    let mut head = reader.take(MAX_HEAD);
+    let mut request_line = String::new();
+    head.read_line(&mut request_line).map_err(|e| e.to_string())?;
+    let mut length = 0;
+    loop {
+        let mut header = String::new();
+        head.read_line(&mut header).map_err(|e| e.to_string())?;
+        if head.limit() == 0 { return Ok(None) }
+        let header = header.trim_end();
+        if header.is_empty() { break }
+        if let Some((name, value)) = header.split_once(':')
+            && name.eq_ignore_ascii_case("content-length") {
+            length = value.trim().parse().map_err(|_| format!("Bad header: {}", header))?;
+        }
+    }
+    Ok(Some((request_line, length)))
+}
+
+fn reply(mut stream: &TcpStream, status: u16, json: &str) -> Result<(), String> {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Content Too Large",
+        431 => "Request Header Fields Too Large",
+        503 => "Service Unavailable",
+        _ => "Unprocessable Content",
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n{}",
+        status, reason, json.len(), json
+    ).map_err(|e| e.to_string())
+}
+
+/// The status code and the JSON body for a request.
+fn respond(method: &str, path: &str, body: &str, timeout: Option<Duration>) -> (u16, String) {
+    let parts = path.trim_matches('/').split('/').collect::<Vec<_>>();
+    let ["day", day, "step", step] = parts[..] else {
+        return (404, error(&format!("No such resource: {}", path)))
+    };
+    if method != "POST" { return (405, error("Only POST is supported")) }
+    let (Ok(day), Ok(step @ 1..=2)) = (day.parse::<usize>(), step.parse::<usize>()) else {
+        return (404, error(&format!("No such step: {}", path)))
+    };
+    let Ok(entry) = registry::find(day) else {
+        return (404, error(&format!("Unknown day: {}", day)))
+    };
+    let lines = body.lines().map(ToOwned::to_owned).collect();
+    let token = Token::new(timeout);
+    let started = Instant::now();
+    let answer = cancel::scope(&token, || (entry.step)(lines, step));
+    let elapsed = started.elapsed().as_secs_f64() * 1000.0;
+    match answer {
+        Ok(a) => (200, format!(
+            "{{\"status\":\"ok\",\"answer\":{},\"elapsed_ms\":{:.3}}}", quote(&a), elapsed
+        )),
+        Err(e) => (422, format!(
+            "{{\"status\":\"error\",\"error\":{},\"elapsed_ms\":{:.3}}}", quote(&e), elapsed
+        )),
+    }
+}
+
+fn error(message: &str) -> String {
+    format!("{{\"status\":\"error\",\"error\":{}}}", quote(message))
+}
+
+/// A JSON string literal.
+fn quote(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_head() {
+        let mut request = "POST /day/1/step/1 HTTP/1.1\r\nContent-Length: 4\r\n\r\nL68\n".as_bytes();
+        assert_eq!(
+            super::read_head(&mut request),
+            Ok(Some(("POST /day/1/step/1 HTTP/1.1\r\n".to_string(), 4)))
+        );
+        assert_eq!(request, b"L68\n");
+        let long = format!("GET / HTTP/1.1\r\nX: {}\r\n\r\n", "a".repeat(MAX_HEAD as usize));
+        assert_eq!(super::read_head(&mut long.as_bytes()), Ok(None));
+        let endless = "X: a\r\n".repeat(MAX_HEAD as usize);
+        assert_eq!(super::read_head(&mut endless.as_bytes()), Ok(None));
+    }
+
+    #[test]
+    fn slot_freed_on_panic() {
+        let active = Arc::new(AtomicUsize::new(1));
+        let slot = Slot(active.clone());
+        let worker = thread::spawn(move || {
+            let _slot = slot;
+            panic!("solver bug");
+        });
+        assert!(worker.join().is_err());
+        assert_eq!(active.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn respond() {
+        let (status, json) = super::respond("POST", "/day/1/step/1", "L68\nL30\nR48\n", None);
+        assert_eq!(status, 200);
+        assert!(json.starts_with("{\"status\":\"ok\",\"answer\":\"1\",\"elapsed_ms\":"));
+        let (status, json) = super::respond("POST", "/day/1/step/1", "X1", None);
+        assert_eq!(status, 422);
+        assert!(json.starts_with("{\"status\":\"error\",\"error\":"));
+        assert_eq!(super::respond("GET", "/day/1/step/1", "", None).0, 405);
+        assert_eq!(super::respond("POST", "/day/1/step/3", "", None).0, 404);
+        assert_eq!(super::respond("POST", "/day/42/step/1", "", None).0, 404);
+        assert_eq!(super::respond("POST", "/", "", None).0, 404);
+    }
+
+    #[test]
+    fn over_tcp() {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Should bind");
+        let address = listener.local_addr().expect("Should be bound");
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().expect("Should connect");
+            handle(stream, None)
+        });
+        let mut client = TcpStream::connect(address).expect("Should connect");
+        let body = "11-22";
+        write!(client, "POST /day/2/step/1 HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}", body.len(), body)
+            .expect("Should send");
+        let mut response = String::new();
+        client.read_to_string(&mut response).expect("Should receive");
+        assert_eq!(server.join().expect("Server should finish"), Ok(()));
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("\"answer\":\"33\""));
+    }
+
+    #[test]
+    fn too_large() {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Should bind");
+        let address = listener.local_addr().expect("Should be bound");
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().expect("Should connect");
+            handle(stream, None)
+        });
+        let mut client = TcpStream::connect(address).expect("Should connect");
+        write!(client, "POST /day/1/step/1 HTTP/1.1\r\nContent-Length: {}\r\n\r\n", 1u64 << 40)
+            .expect("Should send");
+        let mut response = String::new();
+        client.read_to_string(&mut response).expect("Should receive");
+        assert_eq!(server.join().expect("Server should finish"), Ok(()));
+        assert!(response.starts_with("HTTP/1.1 413 Content Too Large\r\n"));
+    }
+
+    #[test]
+    fn quote() {
+        assert_eq!(super::quote("a \"b\"\n\\"), "\"a \\\"b\\\"\\n\\\\\"");
+    }
+}
@@ -0,0 +1,46 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+/// Compares `actual` to the stored `src/snapshots/{name}.snap`, panicking
+/// on a difference. Run the tests with `UPDATE_SNAPSHOTS=1` to store
+/// the current output instead.
+pub fn check(name: &str, actual: &str) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src/snapshots")
+        .join(format!("{}.snap", name));
+    if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::create_dir_all(path.parent().expect("Should have a parent"))
+            .and_then(|_| fs::write(&path, actual))
+            .unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+        return
+    }
+    let Ok(expected) = fs::read_to_string(&path) else {
+        panic!("No snapshot {}, run with UPDATE_SNAPSHOTS=1 to create it", path.display())
+    };
+    if let Some(line) = first_difference(&expected, actual) {
+        panic!(
+            "Snapshot {} differs at line {}:\n- {}\n+ {}\n\
+             run with UPDATE_SNAPSHOTS=1 if the change is intended",
+            name, line + 1,
+            expected.lines().nth(line).unwrap_or(""),
+            actual.lines().nth(line).unwrap_or("")
+        )
+    }
+}
+
+/// The first line that differs, counting a missing line as different.
+fn first_difference(expected: &str, actual: &str) -> Option<usize> {
+    let (e, a) = (expected.lines().collect::<Vec<_>>(), actual.lines().collect::<Vec<_>>());
+    (0..e.len().max(a.len())).find(|&i| e.get(i) != a.get(i))
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn first_difference() {
+        assert_eq!(super::first_difference("a\nb\n", "a\nb\n"), None);
+        assert_eq!(super::first_difference("a\nb\n", "a\nc\n"), Some(1));
+        assert_eq!(super::first_difference("a\n", "a\nb\n"), Some(1));
+    }
+}
@@ -0,0 +1,118 @@
+use std::path::Path;
+use std::time::Duration;
+
+use crate::cache::Cache;
+use crate::ledger::{self, Attempt, Check};
+use crate::registry::Entry;
+use crate::types::slug;
+
+/// What we know about a step from its last run and the ledger.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StepState { NotRun, Todo, Error, Answered, Wrong, Correct }
+
+impl StepState {
+    fn symbol(self) -> char {
+        match self {
+            StepState::NotRun => '-',
+            StepState::Todo => '.',
+            StepState::Error => 'x',
+            StepState::Answered => '+',
+            StepState::Wrong => '?',
+            StepState::Correct => '*',
+        }
+    }
+}
+
+pub struct DayStatus {
+    pub day: usize,
+    pub input: bool,
+    pub steps: [StepState; 2],
+    pub elapsed: Option<Duration>,
+}
+
+pub fn day_status(
+    entry: &Entry, input_dir: &Path, cache: &Cache, attempts: &[Attempt]
+) -> DayStatus {
+    let day = slug(&(entry.name)());
+    let runs = [1, 2].map(|step| cache.last_run(&day, step));
+    let steps = [0, 1].map(|i| match runs[i].as_ref().map(|r| &r.answer) {
+        None => StepState::NotRun,
+        Some(Err(_)) => StepState::Error,
+        Some(Ok(a)) if a == "TODO" => StepState::Todo,
+        Some(Ok(a)) => match ledger::check(attempts, i + 1, a) {
+            Check::Correct => StepState::Correct,
+            Check::Wrong(_) => StepState::Wrong,
+            Check::Unknown => StepState::Answered,
+        },
+    });
+    let elapsed = runs.iter().flatten().map(|r| r.elapsed).reduce(|a, b| a + b);
+    DayStatus {
+        day: entry.day,
+        input: input_dir.join((entry.input_file)()).exists(),
+        steps,
+        elapsed,
+    }
+}
+
+/// A Monday-first calendar, December 2025 starts on a Monday.
+pub fn render(days: &[DayStatus]) -> Vec<String> {
+    const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+    let mut lines = vec![
+        WEEKDAYS.iter().map(|d| format!("{:<10}", d)).collect::<Vec<_>>().join(" ")
+            .trim_end().to_string()
+    ];
+    for week in days.chunks(7) {
+        lines.push(week.iter().map(cell).collect::<Vec<_>>().join(" ").trim_end().to_string());
+    }
+    lines.push(String::new());
+    lines.push(
+        "* correct  + answered  ? wrong  x error  . TODO  - not run  ! no input".to_string()
+    );
+    lines
+}
+
+fn cell(status: &DayStatus) -> String {
+    format!(
+        "{:02}{}{}{} {:>4}",
+        status.day,
+        if status.input { ' ' } else { '!' },
+        status.steps[0].symbol(),
+        status.steps[1].symbol(),
+        status.elapsed.map(short_duration).unwrap_or_default(),
+    )
+}
+
+/// At most four characters wide for anything under 100 seconds.
+pub fn short_duration(d: Duration) -> String {
+    match d.as_micros() {
+        0..1000 => "<1ms".to_string(),
+        1000..1_000_000 => format!("{}ms", d.as_millis()),
+        _ => format!("{:.1}s", d.as_secs_f64()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render() {
+        let days = (1..=8).map(|day| DayStatus {
+            day,
+            input: day != 8,
+            steps: [StepState::Correct, if day == 2 { StepState::Todo } else { StepState::NotRun }],
+            elapsed: (day == 1).then_some(Duration::from_millis(12)),
+        }).collect::<Vec<_>>();
+        let lines = super::render(&days);
+        assert!(lines[0].starts_with("Mon        Tue"));
+        assert!(lines[1].starts_with("01 *- 12ms 02 *.      03 *-"));
+        assert_eq!(lines[2], "08!*-");
+    }
+
+    #[test]
+    fn short_duration() {
+        assert_eq!(super::short_duration(Duration::from_micros(340)), "<1ms");
+        assert_eq!(super::short_duration(Duration::from_millis(5)), "5ms");
+        assert_eq!(super::short_duration(Duration::from_millis(2500)), "2.5s");
+    }
+}
@@ -1,32 +1,208 @@
-use std::process::exit;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use crate::{audit, cancel, identify, memory, progress};
+use crate::cache::{Cache, Key, LastRun};
+use crate::certificate::Certificate;
+use crate::input::read_lines;
+use crate::ledger::{self, Attempt, Check, Ledger};
+use crate::rng::Rng;
 
 pub trait Day {
     type Input1;
     type Input2;
 
     fn get_name(&self) -> String;
+    /// The input's file name within the input directory.
+    fn input_file(&self) -> String;
 
     fn input1(&self) -> &Self::Input1;
     fn input2(&self) -> &Self::Input2;
 
-    fn step1(&self, input: &Self::Input1);
-    fn step2(&self, input: &Self::Input2);
+    fn step1(&self, input: &Self::Input1) -> Result<String, String>;
+    fn step2(&self, input: &Self::Input2) -> Result<String, String>;
 
     fn new() -> Self;
-    fn setup(&mut self) -> Result<(), String>;
-    fn run(&mut self) {
-        if let Err(err) = run_result(self) {
-            println!("Error: {}", err);
-            exit(1);
-        };
+    /// Random valid input lines, `size` roughly scales the puzzle.
+    fn generate(rng: &mut Rng, size: usize) -> Vec<String>;
+    fn parse(&mut self, lines: Vec<String>) -> Result<(), String>;
+    /// The step's answer with a witness of it for `certificate::check`,
+    /// if the day has any, both from the same solve.
+    fn certified(&self, step: usize) -> Result<(String, Option<Certificate>), String> {
+        Ok((solve(self, step, None)?, None))
+    }
+    /// A picture of the parsed input for reports, as a standalone SVG.
+    fn svg(&self) -> Option<String> { None }
+    /// Usages of the questions `query` answers about the parsed input.
+    fn queries(&self) -> &'static [&'static str] { &[] }
+    fn query(&self, name: &str, _args: &[&str]) -> Result<String, String> {
+        Err(unknown_query(self.queries(), name))
+    }
+    /// Names of other solvers of a step, besides `step1` and `step2`.
+    fn variants(&self, _step: usize) -> &'static [&'static str] { &[] }
+    /// Solves a step with one of its `variants`.
+    fn variant(&self, step: usize, name: &str) -> Result<String, String> {
+        Err(unknown_variant(step, name))
+    }
+    /// Solves both steps, collecting the report in `out`.
+    fn run(&mut self, options: &Options, out: &mut Output) -> Result<(), String> {
+        run_result(self, options, out)
+    }
+}
+
+/// How the runner runs the days.
+pub struct Options {
+    pub input_dir: PathBuf,
+    pub timeout: Option<Duration>,
+    pub cache: Option<Cache>,
+    pub ledger: Ledger,
+    /// Report the allocations of every phase.
+    pub memory: bool,
+    /// The solver variant to run for the steps that have it.
+    pub variant: Option<String>,
+}
+
+/// The report of a run, printed line by line as it comes, or kept
+/// until the day is done.
+pub struct Output {
+    lines: Vec<String>,
+    live: bool,
+}
+
+impl Output {
+    pub fn live() -> Output { Output { lines: Vec::new(), live: true } }
+    pub fn buffered() -> Output { Output { lines: Vec::new(), live: false } }
+
+    pub fn push(&mut self, line: String) {
+        if self.live { println!("{}", line) } else { self.lines.push(line) }
+    }
+
+    /// The kept lines, none when live.
+    pub fn into_lines(self) -> Vec<String> { self.lines }
+}
+
+/// The usage of a query called with wrong arguments, or that there is none.
+pub fn unknown_query(queries: &[&str], name: &str) -> String {
+    queries.iter()
+        .find(|q| q.split_whitespace().next() == Some(name))
+        .map(|q| format!("Usage: {}", q))
+        .unwrap_or(format!("Unknown query: {}", name))
+}
+
+pub fn unknown_variant(step: usize, name: &str) -> String {
+    format!("Unknown variant of step {}: {}", step, name)
+}
+
+/// Solves a step with the named variant, or the default solver.
+pub fn solve<T: Day + ?Sized>(
+    day: &T, step: usize, variant: Option<&str>
+) -> Result<String, String> {
+    match (step, variant) {
+        (1 | 2, Some(name)) => day.variant(step, name),
+        (1, None) => day.step1(day.input1()),
+        (2, None) => day.step2(day.input2()),
+        _ => Err(format!("Unknown step: {}", step)),
     }
 }
 
+/// File-friendly day name: "Day 01" becomes "day01".
+pub fn slug(name: &str) -> String {
+    name.to_lowercase().replace(' ', "")
+}
+
 #[inline]
-fn run_result<T: Day + ?Sized>(day: &mut T) -> Result<(), String> {
-    println!("{}", day.get_name());
-    day.setup()?;
-    day.step1(day.input1());
-    day.step2(day.input2());
+fn run_result<T: Day + ?Sized>(
+    day: &mut T, options: &Options, out: &mut Output
+) -> Result<(), String> {
+    let name = day.get_name();
+    out.push(name.clone());
+    let path = options.input_dir.join(day.input_file());
+    let lines = read_lines(&path)?;
+    let keys = [1, 2].map(|step| Key::new(&name, step, &lines));
+    let variants = [1, 2].map(|step| options.variant.as_deref()
+        .filter(|v| day.variants(step).contains(v)));
+    // a chosen variant has to actually run
+    let cached = [0, 1].map(|i| match variants[i] {
+        Some(_) => None,
+        None => options.cache.as_ref().and_then(|c| c.get(&keys[i])),
+    });
+    if cached.iter().any(Option::is_none) {
+        let (parsed, stats) = memory::measure(|| day.parse(lines));
+        if let Err(e) = parsed {
+            // the parser took the lines, read them again for the hint
+            let others = read_lines(&path).map(|lines| identify::other_days(&name, &lines))
+                .unwrap_or_default();
+            if !others.is_empty() {
+                out.push(format!("Warning: the input looks like {}", others.join(" or ")));
+            }
+            return Err(e)
+        }
+        if options.memory { out.push(format!("Parse memory: {}", stats)) }
+    }
+    let (attempts, warnings) = options.ledger.attempts(Some(&slug(&name)));
+    for warning in warnings { out.push(format!("Warning: {}", warning)) }
+    for (i, key) in keys.iter().enumerate() {
+        let step = i + 1;
+        if let Some(answer) = &cached[i] {
+            print_answer(out, step, None, Ok(answer.clone()), true, &attempts);
+            continue;
+        }
+        // every step gets the whole timeout
+        let token = cancel::Token::new(options.timeout);
+        let started = Instant::now();
+        let (answer, stats) = memory::measure(
+            || run_step(&name, step, &token, || solve(day, step, variants[i]))
+        );
+        // only the default solver's answers and timings are cached
+        // writes are best effort, and a placeholder is no answer to keep
+        let written = match options.cache.as_ref().filter(|_| variants[i].is_none()) {
+            Some(cache) => {
+                let run = LastRun { answer: answer.clone(), elapsed: started.elapsed() };
+                match &answer {
+                    Ok(a) if a != "TODO" => cache.put(key, a),
+                    _ => Ok(()),
+                }.and_then(|_| cache.put_last_run(&key.day, step, &run))
+            },
+            None => Ok(()),
+        };
+        print_answer(out, step, variants[i], answer, false, &attempts);
+        if let Err(e) = written { out.push(format!("Warning: not cached: {}", e)) }
+        if options.memory { out.push(format!("Step {} memory: {}", step, stats)) }
+    }
     Ok(())
 }
+
+/// Runs a step with progress reporting, cancellation and auditing set up.
+fn run_step<F>(
+    name: &str, step: usize, token: &cancel::Token, f: F
+) -> Result<String, String>
+where F: FnOnce() -> Result<String, String> {
+    let label = format!("{} step {}", name, step);
+    progress::track(&label, || cancel::scope(token, || audit::within(&label, f)))
+}
+
+fn print_answer(
+    out: &mut Output, step: usize, variant: Option<&str>,
+    answer: Result<String, String>, cached: bool, attempts: &[Attempt]
+) {
+    let label = match variant {
+        Some(v) => format!("Step {} ({})", step, v),
+        None => format!("Step {}", step),
+    };
+    let a = match answer {
+        Ok(a) => a,
+        Err(e) => return out.push(format!("{}: Error: {}", label, e)),
+    };
+    let check = ledger::check(attempts, step, &a);
+    let notes = [(cached, "cached"), (check == Check::Correct, "correct")]
+        .iter().filter(|(on, _)| *on).map(|(_, n)| *n)
+        .collect::<Vec<_>>();
+    if notes.is_empty() {
+        out.push(format!("{}: {}", label, a));
+    } else {
+        out.push(format!("{}: {} ({})", label, a, notes.join(", ")));
+    }
+    if let Check::Wrong(reason) = check {
+        out.push(format!("Warning: {}", reason));
+    }
+}
@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::thread::sleep;
+use std::time::Duration;
+
+use crate::cache::hash;
+use crate::cancel::{self, Token};
+use crate::input::read_lines;
+use crate::registry::{Answers, Entry};
+
+const POLL: Duration = Duration::from_millis(500);
+
+/// Re-solves the day whenever its input or example changes, forever.
+pub fn watch(entry: &Entry, input_dir: &Path, timeout: Option<Duration>) -> Result<(), String> {
+    let files = watched_files(&input_dir.join((entry.input_file)()).to_string_lossy());
+    println!("Watching {}", files.join(", "));
+    let mut seen: HashMap<&String, (u64, Result<Answers, String>)> = HashMap::new();
+    loop {
+        for file in &files {
+            // a missing file is fine, it may show up later
+            let Ok(lines) = read_lines(file) else { continue };
+            let input = hash(&lines);
+            if seen.get(file).is_some_and(|(h, _)| *h == input) { continue }
+            let token = Token::new(timeout);
+            let answers = cancel::scope(&token, || (entry.solve)(lines));
+            println!("== {}", file);
+            let previous = seen.get(file).map(|(_, a)| a);
+            for line in changes(previous, &answers) { println!("{}", line) }
+            seen.insert(file, (input, answers));
+        }
+        sleep(POLL);
+    }
+}
+
+/// The input file itself and its `_example` sibling.
+fn watched_files(input_file: &str) -> Vec<String> {
+    match input_file.strip_suffix(".txt") {
+        Some(base) if !base.ends_with("_example") =>
+            vec![input_file.to_string(), format!("{}_example.txt", base)],
+        _ => vec![input_file.to_string()],
+    }
+}
+
+/// Answers of a run, each annotated with how it differs from the previous one.
+fn changes(
+    previous: Option<&Result<Answers, String>>,
+    current: &Result<Answers, String>,
+) -> Vec<String> {
+    let text = |answer: &Result<String, String>| match answer {
+        Ok(a) => a.clone(),
+        Err(e) => format!("Error: {}", e),
+    };
+    let answers = match current {
+        Ok(answers) => answers,
+        Err(e) => return vec![format!("Error: {}", e)],
+    };
+    answers.iter().enumerate().map(|(i, answer)| {
+        let now = text(answer);
+        let note = match previous {
+            None => String::new(),
+            Some(Err(e)) => format!(" (was Error: {})", e),
+            Some(Ok(before)) if text(&before[i]) == now => " (unchanged)".to_string(),
+            Some(Ok(before)) => format!(" (was {})", text(&before[i])),
+        };
+        format!("Step {}: {}{}", i + 1, now, note)
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn watched_files() {
+        assert_eq!(
+            super::watched_files("input/day01.txt"),
+            vec!["input/day01.txt", "input/day01_example.txt"]
+        );
+        assert_eq!(
+            super::watched_files("input/day10_example.txt"),
+            vec!["input/day10_example.txt"]
+        );
+    }
+
+    #[test]
+    fn changes() {
+        let first: Result<Answers, String> = Ok([Ok("3".to_string()), Ok("6".to_string())]);
+        let second = Ok([Ok("3".to_string()), Err("Oops".to_string())]);
+        assert_eq!(super::changes(None, &first), vec!["Step 1: 3", "Step 2: 6"]);
+        assert_eq!(
+            super::changes(Some(&first), &second),
+            vec!["Step 1: 3 (unchanged)", "Step 2: Error: Oops (was 6)"]
+        );
+        assert_eq!(
+            super::changes(Some(&Err("Bad line".to_string())), &first),
+            vec!["Step 1: 3 (was Error: Bad line)", "Step 2: 6 (was Error: Bad line)"]
+        );
+    }
+}